@@ -1,6 +1,6 @@
 use crate::{reduce, test, Selection};
 use anyhow::Result;
-use std::fs;
+use std::{fs, path::Path};
 
 const FLAGS: &[&str] = &[
     "--cfg fuzzing_afl",
@@ -61,6 +61,12 @@ pub(crate) fn test(selection: &Selection, test_args: &test::Args) -> Result<()>
     Ok(())
 }
 
-pub(crate) fn reduce(_selection: &Selection, _reduce_args: &reduce::Args) -> Result<()> {
-    todo!()
+/// AFL inputs are read by the generators the same way as the other engines, so the corpus is
+/// replayed by the random engine
+///
+/// The inputs that AFL found are in its queue rather than the corpus, so they're reduced along with
+/// the corpus.
+pub(crate) fn reduce(selection: &Selection, reduce_args: &reduce::Args) -> Result<()> {
+    let queue = Path::new("afl_state").join("queue");
+    crate::random::reduce_with(selection, reduce_args, &[queue])
 }
@@ -49,7 +49,7 @@ impl Engine {
             #[cfg(feature = "kani")]
             Self::Kani => Ok(()),
 
-            Self::Random => Ok(()),
+            Self::Random => crate::random::reduce(selection, args),
         }
     }
 }
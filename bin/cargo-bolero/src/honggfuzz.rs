@@ -79,8 +79,14 @@ pub(crate) fn test(selection: &Selection, test_args: &test::Args) -> Result<()>
 
 pub(crate) fn reduce(selection: &Selection, reduce: &reduce::Args) -> Result<()> {
     let test_target = selection.test_target(FLAGS, "honggfuzz")?;
-    let corpus_dir = test_target.default_corpus_dir();
-    let crashes_dir = test_target.default_crashes_dir();
+    let corpus_dir = reduce
+        .corpus_dir
+        .clone()
+        .unwrap_or_else(|| test_target.default_corpus_dir());
+    let crashes_dir = reduce
+        .crashes_dir
+        .clone()
+        .unwrap_or_else(|| test_target.default_crashes_dir());
 
     fs::create_dir_all(&corpus_dir)?;
     fs::create_dir_all(&crashes_dir)?;
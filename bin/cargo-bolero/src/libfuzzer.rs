@@ -79,7 +79,10 @@ pub(crate) fn test(selection: &Selection, test_args: &test::Args) -> Result<()>
 
 pub(crate) fn reduce(selection: &Selection, reduce: &reduce::Args) -> Result<()> {
     let test_target = selection.test_target(FLAGS, "libfuzzer")?;
-    let corpus_dir = test_target.default_corpus_dir();
+    let corpus_dir = reduce
+        .corpus_dir
+        .clone()
+        .unwrap_or_else(|| test_target.default_corpus_dir());
     let tmp_corpus = test_target.temp_dir()?;
 
     fs::create_dir_all(&corpus_dir)?;
@@ -1,6 +1,6 @@
 use crate::{
-    build_clusterfuzz::BuildClusterfuzz, list::List, new::New, reduce::Reduce,
-    selection::Selection, test::Test,
+    build_clusterfuzz::BuildClusterfuzz, list::List, new::New, reduce::Reduce, replay::Replay,
+    selection::Selection, test::Test, tmin::Tmin,
 };
 use anyhow::{anyhow, Context, Result};
 use std::io::Write;
@@ -21,15 +21,19 @@ mod new;
 mod project;
 mod random;
 mod reduce;
+mod replay;
 mod selection;
 mod test;
 mod test_target;
+mod tmin;
 
 #[derive(Debug, StructOpt)]
 #[allow(clippy::large_enum_variant)]
 enum Commands {
     Test(Test),
     Reduce(Reduce),
+    Replay(Replay),
+    Tmin(Tmin),
     New(New),
     List(List),
     BuildClusterfuzz(BuildClusterfuzz),
@@ -40,6 +44,8 @@ impl Commands {
         match self {
             Self::Test(cmd) => cmd.exec(),
             Self::Reduce(cmd) => cmd.exec(),
+            Self::Replay(cmd) => cmd.exec(),
+            Self::Tmin(cmd) => cmd.exec(),
             Self::New(cmd) => cmd.exec(),
             Self::List(cmd) => cmd.exec(),
             Self::BuildClusterfuzz(cmd) => cmd.exec(),
@@ -1,22 +1,38 @@
-use crate::{exec, test, test_target::TestTarget, Selection};
-use anyhow::Result;
+use crate::{exec, reduce, test, test_target::TestTarget, Selection};
+use anyhow::{anyhow, Context, Result};
 use core::time::Duration;
 use serde::Deserialize;
 use std::{
-    io::{BufRead, BufReader},
-    process::{Child, Stdio},
+    collections::{hash_map::DefaultHasher, HashSet},
+    fs,
+    hash::{Hash, Hasher},
+    io::{BufRead, BufReader, Cursor, Write},
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
     sync::mpsc,
     time::Instant,
 };
 
-const FLAGS: &[&str] = &["--cfg fuzzing_random"];
+pub(crate) const FLAGS: &[&str] = &["--cfg fuzzing_random"];
+
+/// Instruments the test so line coverage can be included in Tyche observations and used to pick
+/// the inputs that parallel workers share
+const TYCHE_FLAGS: &[&str] = &["-Cinstrument-coverage", "--cfg bolero_coverage"];
 
 pub(crate) fn test(selection: &Selection, test_args: &test::Args) -> Result<()> {
-    let test_target = selection.test_target(FLAGS, "random")?;
+    let mut flags = FLAGS.to_vec();
 
     let jobs = test_args.jobs.unwrap_or(1);
 
+    if is_instrumented(jobs) {
+        flags.extend(TYCHE_FLAGS);
+    }
+
+    let test_target = selection.test_target(&flags, "random")?;
+
     if jobs > 1 {
+        let mut corpus = SharedCorpus::new(corpus_dir(&test_target, test_args))?;
+
         let (sender, recv) = mpsc::channel();
         for id in 0..jobs {
             let args = (id, sender.clone());
@@ -25,7 +41,7 @@ pub(crate) fn test(selection: &Selection, test_args: &test::Args) -> Result<()>
         }
 
         let mut total = TotalStats::default();
-        let mut remaining = 0;
+        let mut remaining = jobs;
         loop {
             match recv.recv_timeout(Duration::from_secs(1)) {
                 Ok(Message::Shutdown { success: false }) => {
@@ -33,12 +49,17 @@ pub(crate) fn test(selection: &Selection, test_args: &test::Args) -> Result<()>
                 }
                 Ok(Message::Shutdown { success: true }) => {
                     remaining -= 1;
-                    if remaining <= 0 {
+                    if remaining == 0 {
                         break;
                     }
                 }
-                Ok(Message::Stats(stats)) => {
-                    total.add(stats);
+                Ok(Message::Stats { worker, stats }) => {
+                    total.add(worker, stats);
+                }
+                Ok(Message::Share { share }) => {
+                    if corpus.insert(&share.token)? {
+                        total.shared += 1;
+                    }
                 }
                 Err(mpsc::RecvTimeoutError::Timeout) => {
                     continue;
@@ -59,6 +80,247 @@ pub(crate) fn test(selection: &Selection, test_args: &test::Args) -> Result<()>
     Ok(())
 }
 
+fn is_tyche_enabled() -> bool {
+    std::env::var_os("BOLERO_TYCHE").is_some_and(|path| !path.is_empty())
+}
+
+/// Returns `true` if the test target is built with `TYCHE_FLAGS`
+fn is_instrumented(jobs: usize) -> bool {
+    // the workers share the inputs that cover new lines
+    is_tyche_enabled() || jobs > 1
+}
+
+/// Returns the corpus that the workers share with each other
+fn corpus_dir(test_target: &TestTarget, test_args: &test::Args) -> PathBuf {
+    test_args
+        .corpus_dir
+        .clone()
+        .unwrap_or_else(|| test_target.default_corpus_dir())
+}
+
+/// Stops a test target built with `TYCHE_FLAGS` from writing profile files
+fn disable_profile_files(cmd: &mut Command) {
+    // the counters are read in-process so there's no need for the profile files
+    if std::env::var_os("LLVM_PROFILE_FILE").is_none() {
+        let null = if cfg!(windows) { "NUL" } else { "/dev/null" };
+        cmd.env("LLVM_PROFILE_FILE", null);
+    }
+}
+
+/// Reduces the corpus to the smallest inputs that cover all of the behaviors of the test
+///
+/// Each corpus input is replayed by the test target, which reports the executed lines or, if the
+/// lines aren't available, a hash of the generated value. The inputs are then picked from
+/// smallest to largest, keeping the ones that cover anything new. Failing inputs are moved to the
+/// crashes directory.
+pub(crate) fn reduce(selection: &Selection, reduce_args: &reduce::Args) -> Result<()> {
+    reduce_with(selection, reduce_args, &[])
+}
+
+/// Reduces the corpus along with the inputs in `extra_dirs`, relative to the work dir of the target
+///
+/// The extra inputs that are kept or failed are copied, and the extra directories are left as
+/// they are.
+pub(crate) fn reduce_with(
+    selection: &Selection,
+    reduce_args: &reduce::Args,
+    extra_dirs: &[PathBuf],
+) -> Result<()> {
+    let mut flags = FLAGS.to_vec();
+    flags.extend(TYCHE_FLAGS);
+
+    let test_target = selection.test_target(&flags, "random")?;
+    let corpus_dir = reduce_args
+        .corpus_dir
+        .clone()
+        .unwrap_or_else(|| test_target.default_corpus_dir());
+    let crashes_dir = reduce_args
+        .crashes_dir
+        .clone()
+        .unwrap_or_else(|| test_target.default_crashes_dir());
+
+    fs::create_dir_all(&corpus_dir)?;
+
+    let mut inputs = vec![];
+    list_inputs(&corpus_dir, false, &mut inputs)?;
+    for dir in extra_dirs {
+        let dir = test_target.workdir().join(dir);
+        if dir.is_dir() {
+            list_inputs(&dir, true, &mut inputs)?;
+        }
+    }
+
+    // no point in reducing an empty corpus
+    if inputs.is_empty() {
+        return Ok(());
+    }
+
+    // prefer the smallest inputs with the same coverage
+    inputs.sort();
+    let inputs: Vec<(PathBuf, bool)> = inputs
+        .into_iter()
+        .map(|(_size, path, is_extra)| (path, is_extra))
+        .collect();
+    let paths: Vec<PathBuf> = inputs.iter().map(|(path, _)| path.clone()).collect();
+
+    let signatures = signatures(&test_target, &paths, &reduce_args.engine_args)?;
+
+    let mut covered = HashSet::new();
+    let mut kept = vec![];
+    let mut failed = vec![];
+    for (input, signature) in inputs.iter().zip(signatures) {
+        if signature.failed {
+            failed.push(input);
+            continue;
+        }
+
+        let prev_len = covered.len();
+        covered.extend(signature.features);
+        if prev_len != covered.len() {
+            kept.push(input);
+        }
+    }
+
+    let tmp_corpus = test_target.temp_dir()?;
+    for (path, is_extra) in &kept {
+        let new_file = tmp_corpus.path().join(path.file_name().unwrap());
+        if *is_extra {
+            fs::copy(path, new_file)?;
+        } else {
+            fs::rename(path, new_file)?;
+        }
+    }
+
+    if !failed.is_empty() {
+        fs::create_dir_all(&crashes_dir)?;
+    }
+    for (path, is_extra) in &failed {
+        let new_file = crashes_dir.join(path.file_name().unwrap());
+        if *is_extra {
+            fs::copy(path, &new_file)?;
+            eprintln!("copied failing input to {}", new_file.display());
+        } else {
+            fs::rename(path, &new_file)?;
+            eprintln!("moved failing input to {}", new_file.display());
+        }
+    }
+
+    let backup = corpus_dir.parent().unwrap().join("_corpus_bkp");
+    fs::rename(&corpus_dir, &backup)?;
+    fs::rename(&tmp_corpus, &corpus_dir)?;
+    fs::remove_dir_all(&backup)?;
+
+    eprintln!(
+        "reduced the corpus from {} to {} inputs",
+        inputs.len(),
+        kept.len()
+    );
+
+    Ok(())
+}
+
+/// Adds the size and path of each input file in `dir` to `inputs`
+fn list_inputs(dir: &Path, is_extra: bool, inputs: &mut Vec<(u64, PathBuf, bool)>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_hidden = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map_or(true, |name| name.starts_with('.'));
+        if path.is_file() && !is_hidden {
+            let size = fs::metadata(&path)?.len();
+            inputs.push((size, path, is_extra));
+        }
+    }
+    Ok(())
+}
+
+/// The behavior of a corpus input, as reported by the test target
+#[derive(Debug, Deserialize)]
+struct Signature {
+    input: usize,
+    failed: bool,
+    features: Vec<u64>,
+}
+
+/// Replays the inputs in order, returning the signature of each one
+///
+/// An input that crashes the test target doesn't report a signature, so the target is restarted
+/// with the inputs that follow it. The engine arguments are passed to the test target.
+fn signatures(
+    test_target: &TestTarget,
+    inputs: &[PathBuf],
+    engine_args: &[String],
+) -> Result<Vec<Signature>> {
+    let mut signatures = vec![];
+
+    while signatures.len() < inputs.len() {
+        let start = signatures.len();
+        let remaining = &inputs[start..];
+
+        let mut list = tempfile::NamedTempFile::new()?;
+        for path in remaining {
+            writeln!(list, "{}", path.display())?;
+        }
+        list.flush()?;
+
+        let mut cmd = test_target.command();
+        disable_profile_files(&mut cmd);
+        cmd.args(engine_args)
+            .env("BOLERO_REPLAY", "1")
+            .env("BOLERO_REDUCE_INPUTS", list.path())
+            .env("BOLERO_SHRINK_TIME_MS", "0")
+            .env_remove("BOLERO_FAILURE_TOKEN")
+            .env_remove("BOLERO_RANDOM_SEED")
+            .env_remove("BOLERO_REPLAY_FILE");
+
+        let output = cmd
+            .output()
+            .with_context(|| format!("running command {:?}", cmd))?;
+
+        for line in Cursor::new(&output.stdout).lines() {
+            let line = line?;
+            let Some(signature) = line.strip_prefix("[bolero-reduce]") else {
+                continue;
+            };
+            let mut signature: Signature = serde_json::from_str(signature)?;
+            signature.input += start;
+            if signature.input != signatures.len() {
+                return Err(anyhow!("test target reported inputs out of order"));
+            }
+            signatures.push(signature);
+        }
+
+        if signatures.len() == inputs.len() {
+            break;
+        }
+
+        if output.status.success() {
+            return Err(anyhow!(
+                "test target stopped after {} of {} inputs",
+                signatures.len(),
+                inputs.len()
+            ));
+        }
+
+        // the next input crashed the test target
+        let input = signatures.len();
+        eprintln!("{} crashed the test target", inputs[input].display());
+        signatures.push(Signature {
+            input,
+            failed: true,
+            features: vec![],
+        });
+    }
+
+    // a target that can't run at all would otherwise fail every input
+    if signatures.iter().all(|signature| signature.failed) {
+        return Err(anyhow!("every corpus input failed"));
+    }
+
+    Ok(signatures)
+}
+
 fn worker(
     test_target: &TestTarget,
     test_args: &test::Args,
@@ -66,6 +328,11 @@ fn worker(
 ) -> Result<()> {
     let mut cmd = test_target.command();
 
+    let workers = test_args.jobs.unwrap_or(1);
+    if is_instrumented(workers) {
+        disable_profile_files(&mut cmd);
+    }
+
     macro_rules! optional_arg {
         ($arg:ident, $env:expr) => {
             if let Some(v) = test_args.$arg {
@@ -77,23 +344,29 @@ fn worker(
     optional_arg!(seed, "BOLERO_RANDOM_SEED");
     optional_arg!(runs, "BOLERO_RANDOM_ITERATIONS");
     optional_arg!(max_input_length, "BOLERO_RANDOM_MAX_LEN");
+    optional_arg!(shrink_jobs, "BOLERO_SHRINK_JOBS");
     if let Some(t) = test_args.time {
         cmd.env("BOLERO_RANDOM_TEST_TIME_MS", t.as_millis().to_string());
     }
-    // TODO implement other options
-    /*
-    /// Maximum amount of time to run a test target before
-    /// failing
-    #[structopt(short, long, default_value = "10s")]
-    pub timeout: Duration,
-    */
+    if let Some(t) = test_args.shrink_time {
+        cmd.env("BOLERO_SHRINK_TIME_MS", t.as_millis().to_string());
+    }
+    if let Some(t) = test_args.timeout {
+        cmd.env("BOLERO_RANDOM_TIMEOUT_MS", t.as_millis().to_string());
+    }
 
     let Some((worker, chan)) = worker_args else {
         exec(cmd)?;
         return Ok(());
     };
 
+    // exhaustive targets split the enumeration between the workers
     cmd.env("BOLERO_RANDOM_WORKER", worker.to_string())
+        .env("BOLERO_RANDOM_WORKERS", workers.to_string())
+        .env(
+            "BOLERO_RANDOM_SHARE_DIR",
+            corpus_dir(test_target, test_args),
+        )
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
@@ -120,11 +393,21 @@ fn handle_worker_out<B: 'static + Send + std::io::Read>(
             match out.read_line(&mut line) {
                 Ok(0) => break,
                 Ok(_len) => {
+                    if let Some(share) = line
+                        .strip_prefix("[bolero-share]")
+                        .and_then(|v| serde_json::from_str(v).ok())
+                    {
+                        if chan.send(Message::Share { share }).is_err() {
+                            break;
+                        }
+                        continue;
+                    }
+
                     if let Some(stats) = line
                         .strip_prefix("[bolero-report]")
                         .and_then(|v| serde_json::from_str(v).ok())
                     {
-                        if chan.send(Message::Stats(stats)).is_err() {
+                        if chan.send(Message::Stats { worker, stats }).is_err() {
                             break;
                         }
                         continue;
@@ -161,14 +444,89 @@ fn handle_worker_status(mut child: Child, chan: mpsc::Sender<Message>) {
 
 #[derive(Debug)]
 enum Message {
-    Stats(Stats),
+    Stats { worker: usize, stats: Stats },
+    Share { share: Share },
     Shutdown { success: bool },
 }
 
+/// An interesting input found by a worker
+#[derive(Debug, Deserialize)]
+struct Share {
+    /// The failure token of the input
+    token: String,
+}
+
+/// The corpus that the workers share with each other
+///
+/// Each input is written to its own file, named after a hash of its contents, so the inputs are
+/// only written once and the ones from previous runs are picked up as well. Inputs that are read
+/// as bytes are written as they are, and rng inputs are written as a `.token` file, the same as
+/// the crashes directory.
+struct SharedCorpus {
+    dir: PathBuf,
+    names: HashSet<String>,
+}
+
+impl SharedCorpus {
+    fn new(dir: PathBuf) -> Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+
+        let mut names = HashSet::new();
+        for entry in std::fs::read_dir(&dir)? {
+            names.insert(entry?.file_name().to_string_lossy().into_owned());
+        }
+
+        Ok(Self { dir, names })
+    }
+
+    /// Writes the input of the token to the corpus, returning `false` if it was already there
+    fn insert(&mut self, token: &str) -> Result<bool> {
+        let mut parts = token.trim().splitn(3, ':');
+        let (Some("bolero1"), Some(kind), Some(hex)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(anyhow!("invalid token {token:?}"));
+        };
+
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|idx| {
+                hex.get(idx..idx + 2)
+                    .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+            })
+            .collect::<Option<Vec<u8>>>()
+            .with_context(|| format!("invalid token {token:?}"))?;
+
+        let mut hasher = DefaultHasher::new();
+        bytes.hash(&mut hasher);
+        let hash = format!("{:016x}", hasher.finish());
+
+        let (name, contents) = match kind {
+            "bytes" => (hash, bytes),
+            "rng" => (
+                format!("{hash}.token"),
+                format!("{}\n", token.trim()).into_bytes(),
+            ),
+            // the other kinds can't be replayed from the corpus
+            _ => return Ok(false),
+        };
+
+        if self.names.contains(&name) {
+            return Ok(false);
+        }
+
+        std::fs::write(self.dir.join(&name), contents)?;
+        self.names.insert(name);
+        Ok(true)
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct Stats {
     iterations: u64,
     valid: u64,
+    /// The worker's estimate of the number of states in an exhaustive enumeration
+    #[serde(default)]
+    estimate: f64,
 }
 
 #[derive(Debug)]
@@ -177,6 +535,10 @@ struct TotalStats {
     window_runs: u64,
     total_valid: u64,
     window_valid: u64,
+    /// The latest estimate of each worker
+    estimates: Vec<f64>,
+    /// The number of inputs the workers shared during this run
+    shared: u64,
     last_print: Instant,
     target_print: Instant,
 }
@@ -189,6 +551,8 @@ impl Default for TotalStats {
             window_runs: 0,
             total_valid: 0,
             window_valid: 0,
+            estimates: vec![],
+            shared: 0,
             last_print: now,
             target_print: now + Duration::from_secs(1),
         }
@@ -196,7 +560,12 @@ impl Default for TotalStats {
 }
 
 impl TotalStats {
-    fn add(&mut self, stats: Stats) {
+    fn add(&mut self, worker: usize, stats: Stats) {
+        if self.estimates.len() <= worker {
+            self.estimates.resize(worker + 1, 0.0);
+        }
+        self.estimates[worker] = stats.estimate;
+
         self.total_runs += stats.iterations;
         self.window_runs += stats.iterations;
         self.total_valid += stats.valid;
@@ -215,15 +584,35 @@ impl TotalStats {
         let prefix = "[supervisor] ";
         let ips = (self.window_runs as f32 / elapsed.as_secs_f32()).round();
 
+        // only exhaustive targets report an estimate, which each worker makes for all of the
+        // states since it walks the prefixes of the other shards. Each state is only counted by
+        // the worker that owns it, so the runs add up to the whole enumeration.
+        let estimate = self.estimates.iter().copied().fold(0.0, f64::max);
+        let estimate = if estimate > 0.0 {
+            let percent = self.total_runs as f64 / estimate * 100.0;
+            format!("\tstate space estimate: {estimate} ({percent:.03}%)")
+        } else {
+            String::new()
+        };
+
+        let shared = if self.shared > 0 {
+            format!("\tshared inputs: {}", self.shared)
+        } else {
+            String::new()
+        };
+
         // only report valid percentage if we drop below 100%
         if self.total_runs == self.total_valid {
-            println!("{prefix}#{}\titerations/s: {ips}", self.total_runs);
+            println!(
+                "{prefix}#{}\titerations/s: {ips}{estimate}{shared}",
+                self.total_runs
+            );
         } else {
             let total_perc = self.total_valid as f32 / self.total_runs as f32 * 100.0;
             let window_perc = self.window_valid as f32 / self.window_runs as f32 * 100.0;
             let vps = (self.window_valid as f32 / elapsed.as_secs_f32()).round();
             println!(
-                "{prefix}#{}\titerations/s: {ips} valid: {} ({:.2}%) valid/s: {vps} ({:.2}%)",
+                "{prefix}#{}\titerations/s: {ips}{estimate}{shared} valid: {} ({:.2}%) valid/s: {vps} ({:.2}%)",
                 self.total_runs, self.total_valid, total_perc, window_perc,
             );
         }
@@ -232,3 +621,36 @@ impl TotalStats {
         self.window_valid = 0;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_corpus_test() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut corpus = SharedCorpus::new(dir.path().to_path_buf()).unwrap();
+
+        assert!(corpus.insert("bolero1:bytes:0102").unwrap());
+        assert!(!corpus.insert("bolero1:bytes:0102").unwrap());
+        assert!(corpus.insert("bolero1:rng:0102").unwrap());
+        assert!(!corpus.insert("bolero1:exhaustive:01").unwrap());
+        assert!(corpus.insert("bolero1:bytes:zz").is_err());
+
+        let mut files: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        files.sort_by_key(|path| path.extension().is_some());
+        assert_eq!(files.len(), 2);
+
+        // bytes are written as they are and rng inputs as a token
+        assert_eq!(fs::read(&files[0]).unwrap(), [1, 2]);
+        assert_eq!(files[1].extension().unwrap(), "token");
+        assert_eq!(fs::read_to_string(&files[1]).unwrap(), "bolero1:rng:0102\n");
+
+        // the inputs of previous runs aren't written again
+        let mut corpus = SharedCorpus::new(dir.path().to_path_buf()).unwrap();
+        assert!(!corpus.insert("bolero1:rng:0102").unwrap());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{engine::Engine, selection::Selection};
 use anyhow::Result;
+use std::path::PathBuf;
 use structopt::StructOpt;
 
 /// Reduce the corpus of a test target with an engine
@@ -24,6 +25,14 @@ impl Reduce {
 
 #[derive(Debug, StructOpt)]
 pub struct Args {
+    /// User-defined location for the corpus folder
+    #[structopt(long)]
+    pub corpus_dir: Option<PathBuf>,
+
+    /// User-defined location for the crashes folder
+    #[structopt(long)]
+    pub crashes_dir: Option<PathBuf>,
+
     /// Additional arguments to pass to the selected engine
     #[structopt(short = "E", long)]
     pub engine_args: Vec<String>,
@@ -0,0 +1,185 @@
+use crate::{random, selection::Selection, test_target::TestTarget};
+use anyhow::{anyhow, Context, Result};
+use bolero_engine::Seed;
+use humantime::Duration;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use structopt::StructOpt;
+
+/// Files written by the engines next to their crash artifacts
+const IGNORED_FILES: &[&str] = &["README.txt", "HONGGFUZZ.REPORT.TXT"];
+
+/// Replay crash files and seeds through the test target
+///
+/// Each input is run by itself without any generated inputs. Failures are shrunk and reported
+/// the same way as `cargo test`.
+#[derive(Debug, StructOpt)]
+pub struct Replay {
+    #[structopt(flatten)]
+    selection: Selection,
+
+    /// Files or directories of inputs to replay
+    ///
+    /// Crash artifacts written by libfuzzer, AFL and honggfuzz are also accepted. If no inputs or
+    /// seeds are given, the crashes of the target are replayed.
+    inputs: Vec<PathBuf>,
+
+    /// Seeds to replay
+    #[structopt(short = "S", long = "seed")]
+    seeds: Vec<Seed>,
+
+    /// Maximum amount of time to spend shrinking a failing input
+    #[structopt(long)]
+    shrink_time: Option<Duration>,
+}
+
+impl Replay {
+    pub fn exec(&self) -> Result<()> {
+        let test_target = self.selection.test_target(random::FLAGS, "random")?;
+
+        let mut inputs = vec![];
+        for path in &self.inputs {
+            artifacts(path, &mut inputs)
+                .with_context(|| format!("reading inputs from {}", path.display()))?;
+        }
+
+        if self.inputs.is_empty() && self.seeds.is_empty() {
+            let workdir = test_target.workdir();
+            for dir in [
+                test_target.default_crashes_dir(),
+                workdir.join("afl_state").join("crashes"),
+                workdir.join("afl_state").join("hangs"),
+            ] {
+                if dir.is_dir() {
+                    artifacts(&dir, &mut inputs)
+                        .with_context(|| format!("reading inputs from {}", dir.display()))?;
+                }
+            }
+        }
+
+        let inputs = inputs
+            .iter()
+            .map(|path| Input::File(path))
+            .chain(self.seeds.iter().map(|seed| Input::Seed(*seed)));
+
+        let mut total = 0;
+        let mut failed = vec![];
+        for input in inputs {
+            total += 1;
+            if !self.run(&test_target, &input)? {
+                failed.push(input.to_string());
+            }
+        }
+
+        if total == 0 {
+            return Err(anyhow!("no inputs to replay"));
+        }
+
+        if failed.is_empty() {
+            eprintln!("replayed {} inputs without failures", total);
+            return Ok(());
+        }
+
+        eprintln!("failing inputs:");
+        for input in &failed {
+            eprintln!("    {}", input);
+        }
+
+        Err(anyhow!("{} of {} inputs failed", failed.len(), total))
+    }
+
+    /// Runs a single input, returning `false` if it failed
+    fn run(&self, test_target: &TestTarget, input: &Input) -> Result<bool> {
+        let mut cmd = test_target.command();
+
+        cmd.env("BOLERO_REPLAY", "1")
+            .env_remove("BOLERO_FAILURE_TOKEN")
+            .env_remove("BOLERO_RANDOM_SEED")
+            .env_remove("BOLERO_REPLAY_FILE");
+
+        match input {
+            Input::File(path) => cmd.env("BOLERO_REPLAY_FILE", path),
+            Input::Seed(seed) => cmd.env("BOLERO_RANDOM_SEED", seed.to_string()),
+        };
+
+        if let Some(t) = self.shrink_time {
+            cmd.env("BOLERO_SHRINK_TIME_MS", t.as_millis().to_string());
+        }
+
+        eprintln!("replaying {}", input);
+
+        let status = cmd
+            .status()
+            .with_context(|| format!("running command {:?}", cmd))?;
+
+        Ok(status.success())
+    }
+}
+
+enum Input<'a> {
+    File(&'a Path),
+    Seed(Seed),
+}
+
+impl core::fmt::Display for Input<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Seed(seed) => write!(f, "BOLERO_RANDOM_SEED={}", seed),
+        }
+    }
+}
+
+/// Collects the inputs at `path`, which can either be a file or a directory of files
+///
+/// The engines write a few reports and hidden state files next to their artifacts, which are
+/// skipped.
+fn artifacts(path: &Path, inputs: &mut Vec<PathBuf>) -> Result<()> {
+    if !path.is_dir() {
+        fs::metadata(path)?;
+        inputs.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    let mut files = vec![];
+    for entry in fs::read_dir(path)? {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if name.starts_with('.') || IGNORED_FILES.contains(&name) {
+            continue;
+        }
+
+        files.push(path);
+    }
+
+    // replay the artifacts in a consistent order
+    files.sort();
+    inputs.extend(files);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seed_test() {
+        // the seeds printed for failures are random u128 values
+        let seed = u64::MAX as Seed + 1;
+        let replay =
+            Replay::from_iter_safe(["replay", "my_test", "--seed", &seed.to_string()]).unwrap();
+        assert_eq!(replay.seeds, [seed]);
+
+        let input = Input::Seed(replay.seeds[0]);
+        assert_eq!(input.to_string(), "BOLERO_RANDOM_SEED=18446744073709551616");
+    }
+}
@@ -18,6 +18,9 @@ pub struct Test {
     selection: Selection,
 }
 
+/// The timeout of a test case if `--timeout` isn't set
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+
 #[derive(Debug, StructOpt)]
 pub struct Args {
     /// Run the engine with an initial seed
@@ -40,10 +43,25 @@ pub struct Args {
 
     /// Maximum amount of time to run a test target before
     /// failing
-    #[structopt(short, long, default_value = "10s")]
-    pub timeout: Duration,
+    ///
+    /// Defaults to 10s. The random engine only times out test cases when this is set.
+    #[structopt(short, long)]
+    pub timeout: Option<Duration>,
+
+    /// Maximum amount of time to spend shrinking a failing input
+    #[structopt(long)]
+    pub shrink_time: Option<Duration>,
+
+    /// Number of shrinking candidates to evaluate at the same time
+    ///
+    /// Only supported by the random engine. Each candidate runs in its own process, or on a worker
+    /// thread for targets built with `with_shrink_threads`.
+    #[structopt(long)]
+    pub shrink_jobs: Option<usize>,
 
     /// Number of parallel jobs
+    ///
+    /// With the random engine, exhaustive targets split their enumeration between the jobs.
     #[structopt(short, long)]
     pub jobs: Option<usize>,
 
@@ -66,7 +84,9 @@ impl Args {
     }
 
     pub fn timeout_as_secs(&self) -> u64 {
-        self.timeout.as_secs().max(1)
+        self.timeout
+            .as_ref()
+            .map_or(DEFAULT_TIMEOUT_SECS, |d| d.as_secs().max(1))
     }
 }
 
@@ -0,0 +1,101 @@
+use crate::{random, selection::Selection};
+use anyhow::{anyhow, Context, Result};
+use humantime::Duration;
+use std::{fs, path::PathBuf};
+use structopt::StructOpt;
+
+/// Minimize a crash file to a new artifact
+///
+/// The failure is shrunk by the test engine. The smallest failing input is written to the output,
+/// along with a `.txt` file containing the generated value and the panic message.
+#[derive(Debug, StructOpt)]
+pub struct Tmin {
+    #[structopt(flatten)]
+    selection: Selection,
+
+    /// The crash file to minimize
+    input: PathBuf,
+
+    /// Where to write the minimized input [default: minimized-from-<input>]
+    #[structopt(short, long)]
+    output: Option<PathBuf>,
+
+    /// Maximum amount of time to spend shrinking
+    #[structopt(long)]
+    shrink_time: Option<Duration>,
+
+    /// Number of processes evaluating shrinking candidates in parallel
+    #[structopt(long)]
+    shrink_jobs: Option<usize>,
+}
+
+impl Tmin {
+    pub fn exec(&self) -> Result<()> {
+        let input_len = fs::metadata(&self.input)
+            .with_context(|| format!("reading {}", self.input.display()))?
+            .len();
+
+        let output = match &self.output {
+            Some(output) => output.clone(),
+            None => {
+                let name = self
+                    .input
+                    .file_name()
+                    .ok_or_else(|| anyhow!("invalid input {}", self.input.display()))?;
+                let mut output = PathBuf::from("minimized-from-");
+                output.as_mut_os_string().push(name);
+                output
+            }
+        };
+        let mut description = output.clone().into_os_string();
+        description.push(".txt");
+        let description = PathBuf::from(description);
+
+        // the test engine only writes the output once the failure is shrunk
+        for path in [&output, &description] {
+            if path.exists() {
+                fs::remove_file(path)
+                    .with_context(|| format!("removing previous {}", path.display()))?;
+            }
+        }
+
+        let test_target = self.selection.test_target(random::FLAGS, "random")?;
+
+        let mut cmd = test_target.command();
+        cmd.env("BOLERO_REPLAY", "1")
+            .env("BOLERO_REPLAY_FILE", &self.input)
+            .env("BOLERO_TMIN_OUTPUT", &output)
+            .env_remove("BOLERO_FAILURE_TOKEN")
+            .env_remove("BOLERO_RANDOM_SEED");
+
+        if let Some(t) = self.shrink_time {
+            cmd.env("BOLERO_SHRINK_TIME_MS", t.as_millis().to_string());
+        }
+        if let Some(jobs) = self.shrink_jobs {
+            cmd.env("BOLERO_SHRINK_JOBS", jobs.to_string());
+        }
+
+        let status = cmd
+            .status()
+            .with_context(|| format!("running command {:?}", cmd))?;
+
+        if status.success() {
+            return Err(anyhow!("{} doesn't fail the test", self.input.display()));
+        }
+
+        let Ok(output_len) = fs::metadata(&output).map(|meta| meta.len()) else {
+            return Err(anyhow!("the failure couldn't be minimized"));
+        };
+
+        eprintln!(
+            "minimized {} from {} to {} bytes",
+            self.input.display(),
+            input_len,
+            output_len
+        );
+        eprintln!("wrote the minimized input to {}", output.display());
+        eprintln!("wrote the description to {}", description.display());
+
+        Ok(())
+    }
+}
@@ -1,15 +1,47 @@
 use crate::{
     panic::{rust_backtrace, PanicError},
-    Seed,
+    shrink, Seed,
 };
 use core::fmt::{Debug, Display};
 
 /// Contains information about a test failure
+///
+/// The struct is non-exhaustive so details can be added to the report; use [`Failure::new`] to
+/// construct it.
 #[derive(Debug)]
+#[non_exhaustive]
 pub struct Failure<Input> {
     pub error: PanicError,
     pub input: Input,
     pub seed: Option<Seed>,
+    /// A versioned token that replays the failure independently of the rng implementation
+    pub token: Option<String>,
+    /// Describes how the input was shrunk, if it was
+    pub shrink: Option<shrink::Report>,
+}
+
+impl<Input> Failure<Input> {
+    pub fn new(error: PanicError, input: Input, seed: Option<Seed>) -> Self {
+        Self {
+            error,
+            input,
+            seed,
+            token: None,
+            shrink: None,
+        }
+    }
+
+    /// Sets the token that replays the failure
+    pub fn with_token(mut self, token: String) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// Sets the report of how the input was shrunk
+    pub fn with_shrink(mut self, report: shrink::Report) -> Self {
+        self.shrink = Some(report);
+        self
+    }
 }
 
 impl<Input: Debug> Display for Failure<Input> {
@@ -21,8 +53,24 @@ impl<Input: Debug> Display for Failure<Input> {
         if let Some(seed) = &self.seed {
             writeln!(f, "BOLERO_RANDOM_SEED={}\n", seed)?;
         }
+        if let Some(token) = &self.token {
+            writeln!(f, "BOLERO_FAILURE_TOKEN={}\n", token)?;
+        }
 
         writeln!(f, "Input: \n{:#?}\n", self.input)?;
+
+        if let Some(report) = self.shrink.as_ref().filter(|r| !r.improvements.is_empty()) {
+            writeln!(f, "Shrunk by: {}\n", report)?;
+        }
+
+        if let Some(report) = self.shrink.as_ref().filter(|r| r.executions > 0) {
+            writeln!(
+                f,
+                "Shrink executions: {} ({} saved by the cache)\n",
+                report.executions, report.cache_hits
+            )?;
+        }
+
         writeln!(f, "Error: \n{}", self.error)?;
 
         if f.alternate() {
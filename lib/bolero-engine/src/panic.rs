@@ -91,6 +91,14 @@ pub fn catch<F: RefUnwindSafe + FnOnce() -> Result<bool, PanicError>>(
         Ok(Ok(v)) => Ok(v),
         Ok(Err(err)) => Err(err),
         Err(err) => {
+            // if an `any::Error` was returned, then the input wasn't valid
+            #[cfg(feature = "any")]
+            if err.downcast_ref::<bolero_generator::any::Error>().is_some() {
+                // the panic hook also captured the error so clear it
+                let _ = take_panic();
+                return Ok(false);
+            }
+
             if let Some(err) = take_panic() {
                 return Err(err);
             }
@@ -102,12 +110,6 @@ pub fn catch<F: RefUnwindSafe + FnOnce() -> Result<bool, PanicError>>(
                 };
             }
 
-            // if an `any::Error` was returned, then the input wasn't valid
-            #[cfg(feature = "any")]
-            if err.downcast_ref::<bolero_generator::any::Error>().is_some() {
-                return Ok(false);
-            }
-
             try_downcast!(PanicInfo, "{}");
             try_downcast!(anyhow::Error, "{}");
             try_downcast!(String, "{}");
@@ -9,6 +9,7 @@ pub struct Options {
     pub iterations: Option<usize>,
     pub max_len: Option<usize>,
     pub seed: Option<Seed>,
+    pub timeout: Option<Duration>,
 }
 
 impl Default for Options {
@@ -18,6 +19,7 @@ impl Default for Options {
             iterations: get_var("BOLERO_RANDOM_ITERATIONS"),
             max_len: get_var("BOLERO_RANDOM_MAX_LEN"),
             seed: get_var("BOLERO_RANDOM_SEED"),
+            timeout: get_var("BOLERO_RANDOM_TIMEOUT_MS").map(Duration::from_millis),
         }
     }
 }
@@ -1,7 +1,15 @@
 use crate::{panic, panic::PanicError, Failure, Seed, Test};
 use bolero_generator::driver;
-use std::time::Instant;
-
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    time::{Duration, Instant},
+};
+
+mod structure;
 #[cfg(test)]
 mod tests;
 
@@ -28,14 +36,172 @@ impl Input for Vec<u8> {
     }
 }
 
+impl Input for &mut Vec<u8> {
+    type Driver<'a>
+        = driver::ByteSliceDriver<'a>
+    where
+        Self: 'a;
+
+    #[inline]
+    fn driver(&self, len: usize, options: &driver::Options) -> Self::Driver<'_> {
+        driver::ByteSliceDriver::new(&self[..len], options)
+    }
+}
+
+/// How often the progress of a long shrink is printed
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Evaluates shrinking candidates outside of the shrinker's test
+///
+/// When the options allow more than one shrink job, the candidates of each transform are
+/// evaluated in batches, one worker thread per candidate. Tests that can be shared between
+/// threads can run the candidate directly, while the others can replay it in a child process.
+pub trait Executor: Sync {
+    /// Returns `true` if the test fails with the given input bytes
+    fn is_failing(&self, input: &[u8]) -> bool;
+}
+
+impl<F: Fn(&[u8]) -> bool + Sync> Executor for F {
+    #[inline]
+    fn is_failing(&self, input: &[u8]) -> bool {
+        (self)(input)
+    }
+}
+
+/// A domain-specific transform that proposes simpler versions of a failing input
+///
+/// Passes run alongside the built-in passes and operate on the same input bytes, which are
+/// read by the generators or, for byte slice targets, passed directly to the test. For example,
+/// a pass could replace a packet with a canonical one or zero its checksum fields.
+pub trait ShrinkPass {
+    /// The name that's reported for each improvement made by the pass
+    fn name(&self) -> &str;
+
+    /// Returns simpler versions of the failing `input`, in the order they should be tried
+    ///
+    /// The first candidate that still fails the test replaces the input.
+    fn candidates(&self, input: &[u8]) -> Vec<Vec<u8>>;
+}
+
+impl fmt::Debug for dyn ShrinkPass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("ShrinkPass").field(&self.name()).finish()
+    }
+}
+
+/// A smaller failing input that was found while shrinking
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Improvement {
+    /// The name of the pass that found the input
+    pub pass: String,
+    /// The length of the input in bytes
+    pub len: usize,
+}
+
+/// Describes how a failing input was shrunk
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    /// Each improvement, in the order it was found
+    pub improvements: Vec<Improvement>,
+    /// The number of times a candidate was executed
+    pub executions: usize,
+    /// The number of candidates that were skipped because they had already been executed
+    pub cache_hits: usize,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // count the improvements of each pass in the order they first appeared
+        let mut counts: Vec<(&str, usize)> = vec![];
+        for improvement in &self.improvements {
+            match counts
+                .iter_mut()
+                .find(|(pass, _)| *pass == improvement.pass)
+            {
+                Some((_, count)) => *count += 1,
+                None => counts.push((&improvement.pass, 1)),
+            }
+        }
+
+        for (idx, (pass, count)) in counts.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{pass} ({count})")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Called with the input bytes each time a smaller failing input is found
+pub type OnBest<'a> = &'a mut dyn FnMut(&[u8]);
+
+/// Optional callbacks for a shrink
+#[derive(Default)]
+pub struct Hooks<'a> {
+    executor: Option<&'a dyn Executor>,
+    on_best: Option<OnBest<'a>>,
+    passes: &'a [Box<dyn ShrinkPass>],
+}
+
+impl<'a> Hooks<'a> {
+    /// Evaluates the candidates with `executor` when more than one shrink job is configured
+    pub fn with_executor(mut self, executor: &'a dyn Executor) -> Self {
+        self.executor = Some(executor);
+        self
+    }
+
+    /// Calls `on_best` with the input bytes each time a smaller failing input is found
+    ///
+    /// This can be used to save the progress of a shrink that might be interrupted.
+    pub fn with_on_best(mut self, on_best: OnBest<'a>) -> Self {
+        self.on_best = Some(on_best);
+        self
+    }
+
+    /// Runs the given passes alongside the built-in passes
+    pub fn with_passes(mut self, passes: &'a [Box<dyn ShrinkPass>]) -> Self {
+        self.passes = passes;
+        self
+    }
+}
+
+impl fmt::Debug for Hooks<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Hooks")
+            .field("executor", &self.executor.is_some())
+            .field("on_best", &self.on_best.is_some())
+            .field("passes", &self.passes)
+            .finish()
+    }
+}
+
 /// Shrink the input to a simpler form
+///
+/// On success, the input is left holding the shrunken bytes.
 pub fn shrink<T: Test, I: Input>(
     test: &mut T,
     input: I,
     seed: Option<Seed>,
     options: &driver::Options,
 ) -> Option<Failure<T::Value>> {
-    Shrinker::new(test, input, seed, options).shrink()
+    shrink_with(test, input, seed, options, Hooks::default())
+}
+
+/// Shrink the input to a simpler form with the provided hooks
+///
+/// On success, the input is left holding the shrunken bytes.
+pub fn shrink_with<'a, T: Test, I: Input>(
+    test: &'a mut T,
+    input: I,
+    seed: Option<Seed>,
+    options: &'a driver::Options,
+    hooks: Hooks<'a>,
+) -> Option<Failure<T::Value>> {
+    Shrinker::new(test, input, seed, options)
+        .with_hooks(hooks)
+        .shrink()
 }
 
 macro_rules! predicate {
@@ -46,14 +212,6 @@ macro_rules! predicate {
     };
 }
 
-macro_rules! shrink_integer {
-    ($current:expr, $check:expr) => {{
-        let mut check = $check;
-
-        (0..($current)).into_iter().find(|value| check(*value))
-    }};
-}
-
 #[derive(Debug)]
 struct Shrinker<'a, T, I> {
     test: &'a mut T,
@@ -62,6 +220,18 @@ struct Shrinker<'a, T, I> {
     end: usize,
     seed: Option<Seed>,
     options: &'a driver::Options,
+    hooks: Hooks<'a>,
+    /// The smallest failing input found so far
+    best: Vec<u8>,
+    /// The name of the pass being applied
+    pass: &'a str,
+    improvements: Vec<Improvement>,
+    /// The outcomes of the candidates that have been executed, keyed by their hash
+    cache: HashMap<u64, Outcome>,
+    executions: usize,
+    cache_hits: usize,
+    start_time: Instant,
+    last_progress: Instant,
     #[cfg(test)]
     snapshot_input: Vec<u8>,
 }
@@ -69,18 +239,33 @@ struct Shrinker<'a, T, I> {
 impl<'a, T: Test, I: Input> Shrinker<'a, T, I> {
     fn new(test: &'a mut T, input: I, seed: Option<Seed>, options: &'a driver::Options) -> Self {
         let len = input.as_ref().len();
+        let now = Instant::now();
         Self {
             temp_input: input.as_ref().to_vec(),
+            best: input.as_ref().to_vec(),
             input,
             end: len,
             test,
             seed,
             options,
+            hooks: Hooks::default(),
+            pass: "",
+            improvements: vec![],
+            cache: HashMap::new(),
+            executions: 0,
+            cache_hits: 0,
+            start_time: now,
+            last_progress: now,
             #[cfg(test)]
             snapshot_input: vec![],
         }
     }
 
+    fn with_hooks(mut self, hooks: Hooks<'a>) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
     fn shrink(mut self) -> Option<Failure<T::Value>> {
         if self.options.shrink_time_or_default().is_zero() {
             return None;
@@ -101,10 +286,18 @@ impl<'a, T: Test, I: Input> Shrinker<'a, T, I> {
 
         let mut was_changed;
         let start_time = Instant::now();
+        self.start_time = start_time;
+        self.last_progress = start_time;
         let shrink_time = self.options.shrink_time_or_default();
 
         loop {
-            was_changed = self.apply_truncation();
+            was_changed = self.apply("structure", |this| {
+                this.apply_structure(start_time, shrink_time, true)
+            });
+
+            was_changed |= self.apply_truncation();
+
+            was_changed |= self.apply_passes(start_time, shrink_time, true);
 
             // empty input means we're done
             if self.end == 0 {
@@ -137,33 +330,114 @@ impl<'a, T: Test, I: Input> Shrinker<'a, T, I> {
             }
         }
 
+        // Replacing a variant can grow the input, and bisecting a value orders its bytes
+        // numerically rather than lexicographically, so these transforms would undo the
+        // byte-level transforms above. Instead, they're applied once the input has settled.
+        self.apply("structure", |this| {
+            this.apply_structure(start_time, shrink_time, false)
+        });
+        self.apply_passes(start_time, shrink_time, false);
+        self.apply("leaf search", |this| {
+            this.apply_leaf_search(start_time, shrink_time)
+        });
+        self.apply_truncation();
+
         panic::capture_backtrace(capture_backtrace);
         let error = self.execute().err()?;
         panic::capture_backtrace(false);
 
         let input = self.generate_value();
+        self.input.as_mut().truncate(self.end);
 
         // restore settings
         panic::forward_panic(forward_panic);
         panic::capture_backtrace(capture_backtrace);
 
-        Some(Failure {
-            seed: self.seed,
-            error,
-            input,
-        })
+        Some(Failure::new(error, input, self.seed).with_shrink(Report {
+            improvements: self.improvements,
+            executions: self.executions,
+            cache_hits: self.cache_hits,
+        }))
     }
 
     fn apply_truncation(&mut self) -> bool {
         self.apply("truncation end", |this| this.apply_truncation_end())
     }
 
+    /// Applies each of the registered passes
+    ///
+    /// If `ordered` is set, only the candidates that are shorter or lexicographically smaller than
+    /// the current input are tried. Otherwise, each pass replaces the input at most once.
+    fn apply_passes(&mut self, start_time: Instant, shrink_time: Duration, ordered: bool) -> bool {
+        let passes = self.hooks.passes;
+        let mut was_changed = false;
+
+        for pass in passes {
+            if start_time.elapsed() > shrink_time {
+                break;
+            }
+
+            was_changed |= self.apply(pass.name(), |this| {
+                this.apply_pass(&**pass, start_time, shrink_time, ordered)
+            });
+        }
+
+        was_changed
+    }
+
+    fn apply_pass(
+        &mut self,
+        pass: &dyn ShrinkPass,
+        start_time: Instant,
+        shrink_time: Duration,
+        ordered: bool,
+    ) -> Result<(), ()> {
+        let mut was_changed = false;
+
+        while start_time.elapsed() <= shrink_time {
+            let prev_input = self.input.as_ref()[..self.end].to_vec();
+
+            let candidates = pass
+                .candidates(&prev_input)
+                .into_iter()
+                .filter(|candidate| {
+                    if ordered {
+                        (candidate.len(), candidate) < (prev_input.len(), &prev_input)
+                    } else {
+                        *candidate != prev_input
+                    }
+                })
+                .take_while(|_| start_time.elapsed() <= shrink_time);
+
+            let found = self.find_failing(candidates, |this, candidate| {
+                this.input.as_mut().clear();
+                this.input.as_mut().extend_from_slice(candidate);
+                this.end = candidate.len();
+            });
+
+            if found.is_none() {
+                // revert
+                self.input.as_mut().clear();
+                self.input.as_mut().extend_from_slice(&prev_input);
+                self.end = prev_input.len();
+                break;
+            }
+
+            was_changed = true;
+
+            // an unordered pass could keep alternating between candidates
+            if !ordered {
+                break;
+            }
+        }
+
+        predicate!(was_changed);
+        Ok(())
+    }
+
     fn apply_truncation_end(&mut self) -> Result<(), ()> {
         let prev_value = self.end;
-        let result = shrink_integer!(prev_value, |end| {
-            self.end = end;
-            self.execute().is_err()
-        });
+        let result = self.find_failing(0..prev_value, |this, end| this.end = *end);
 
         if let Some(end) = result {
             self.end = end;
@@ -175,6 +449,121 @@ impl<'a, T: Test, I: Input> Shrinker<'a, T, I> {
         }
     }
 
+    /// Applies the transforms derived from the generator structure until none of them succeed
+    ///
+    /// If `ordered` is set, only the candidates that are shorter or lexicographically smaller than
+    /// the current input are tried.
+    fn apply_structure(
+        &mut self,
+        start_time: Instant,
+        shrink_time: Duration,
+        ordered: bool,
+    ) -> Result<(), ()> {
+        let mut was_changed = false;
+
+        'record: while start_time.elapsed() <= shrink_time {
+            let spans = self.record_structure();
+            let prev_input = self.input.as_ref()[..self.end].to_vec();
+
+            let candidates = structure::candidates(&prev_input, &spans)
+                .filter(|candidate| {
+                    !ordered || (candidate.len(), candidate) < (prev_input.len(), &prev_input)
+                })
+                .take_while(|_| start_time.elapsed() <= shrink_time);
+
+            let found = self.find_failing(candidates, |this, candidate| {
+                this.input.as_mut().clear();
+                this.input.as_mut().extend_from_slice(candidate);
+                this.end = candidate.len();
+            });
+
+            if found.is_some() {
+                // the offsets have moved so the structure needs to be recorded again
+                was_changed = true;
+                continue 'record;
+            }
+
+            // revert
+            self.input.as_mut().clear();
+            self.input.as_mut().extend_from_slice(&prev_input);
+            self.end = prev_input.len();
+            break;
+        }
+
+        predicate!(was_changed);
+        Ok(())
+    }
+
+    /// Bisects each primitive value towards its lower bound
+    fn apply_leaf_search(&mut self, start_time: Instant, shrink_time: Duration) -> Result<(), ()> {
+        let mut was_changed = false;
+        let mut leaf = 0;
+
+        while start_time.elapsed() <= shrink_time {
+            // minimizing a value can change how many bytes the following values consume
+            let spans = self.record_structure();
+            let Some(span) = spans
+                .iter()
+                .filter(|span| matches!(span.kind, structure::Kind::Leaf))
+                .nth(leaf)
+            else {
+                break;
+            };
+            leaf += 1;
+
+            let range = span.start..span.end;
+            let width = range.len();
+            if width > 16 {
+                continue;
+            }
+
+            let mut bytes = [0u8; 16];
+            bytes[..width].copy_from_slice(&self.input.as_ref()[range.clone()]);
+            let prev_value = u128::from_le_bytes(bytes);
+
+            let mut failing = prev_value;
+            let mut passing = 0;
+
+            // the bisection never runs its lower end, so check the lower bound first
+            if failing > 0 {
+                self.input.as_mut()[range.clone()].fill(0);
+                if self.is_failing() {
+                    failing = 0;
+                }
+            }
+
+            while passing + 1 < failing && start_time.elapsed() <= shrink_time {
+                let value = passing + (failing - passing) / 2;
+                self.input.as_mut()[range.clone()].copy_from_slice(&value.to_le_bytes()[..width]);
+
+                if self.is_failing() {
+                    failing = value;
+                } else {
+                    passing = value;
+                }
+            }
+
+            self.input.as_mut()[range].copy_from_slice(&failing.to_le_bytes()[..width]);
+            if failing != prev_value {
+                was_changed = true;
+                self.on_best();
+            }
+        }
+
+        predicate!(was_changed);
+        Ok(())
+    }
+
+    fn record_structure(&mut self) -> Vec<structure::Span> {
+        let mut input = RecordInput {
+            input: &self.input.as_ref()[..self.end],
+            options: self.options,
+            spans: vec![],
+        };
+        let _ = self.test.generate_value(&mut input);
+        input.spans
+    }
+
     fn apply_transforms(&mut self, index: usize, was_changed: &mut bool) {
         *was_changed |= self.apply("remove chunk", |this| this.apply_remove_chunk(index));
 
@@ -190,9 +579,8 @@ impl<'a, T: Test, I: Input> Shrinker<'a, T, I> {
 
     fn apply_byte_shrink(&mut self, index: usize) -> Result<(), ()> {
         let prev_value = self.input.as_ref()[index];
-        let result = shrink_integer!(prev_value, |value| {
-            self.input.as_mut()[index] = value;
-            self.execute().is_err()
+        let result = self.find_failing(0..prev_value, |this, value| {
+            this.input.as_mut()[index] = *value;
         });
 
         let input = self.input.as_mut();
@@ -223,8 +611,8 @@ impl<'a, T: Test, I: Input> Shrinker<'a, T, I> {
         // we need at least one byte to shift up
         let max_len = temp_input.len() - 1;
 
-        let result = shrink_integer!(max_len, |diff| {
-            self.input.as_mut().truncate(index);
+        let result = self.find_failing(0..max_len, |this, diff| {
+            this.input.as_mut().truncate(index);
             let offset = max_len - diff;
             let slice = &temp_input[offset..];
 
@@ -234,9 +622,8 @@ impl<'a, T: Test, I: Input> Shrinker<'a, T, I> {
                 assert_eq!(slice.len(), diff + 1);
             }
 
-            self.input.as_mut().extend_from_slice(slice);
-            self.end = self.input.len();
-            self.execute().is_err()
+            this.input.as_mut().extend_from_slice(slice);
+            this.end = this.input.len();
         });
 
         self.input.as_mut().truncate(index);
@@ -263,7 +650,7 @@ impl<'a, T: Test, I: Input> Shrinker<'a, T, I> {
 
         self.input.as_mut().swap(index, index + 1);
 
-        if self.execute().is_err() {
+        if self.is_failing() {
             return Ok(());
         }
 
@@ -274,7 +661,9 @@ impl<'a, T: Test, I: Input> Shrinker<'a, T, I> {
     }
 
     #[inline(always)]
-    fn apply<F: FnOnce(&mut Self) -> Result<(), ()>>(&mut self, transform: &str, f: F) -> bool {
+    fn apply<F: FnOnce(&mut Self) -> Result<(), ()>>(&mut self, transform: &'a str, f: F) -> bool {
+        self.pass = transform;
+
         // store a snapshot of the previous input
         #[cfg(test)]
         {
@@ -301,17 +690,182 @@ impl<'a, T: Test, I: Input> Shrinker<'a, T, I> {
             }
         }
 
-        let _ = transform;
-
         result
     }
 
+    /// Returns the first candidate that fails the test, leaving it applied to the input
+    ///
+    /// `apply` replaces the input with a candidate. With more than one shrink job and an
+    /// executor, the candidates are evaluated in batches on worker threads and the first one in
+    /// order is confirmed with the shrinker's test.
+    fn find_failing<C, A>(
+        &mut self,
+        candidates: impl IntoIterator<Item = C>,
+        mut apply: A,
+    ) -> Option<C>
+    where
+        A: FnMut(&mut Self, &C),
+    {
+        let mut candidates = candidates.into_iter();
+        let jobs = self.options.shrink_jobs_or_default();
+
+        let Some(executor) = self.hooks.executor.filter(|_| jobs > 1) else {
+            let found = candidates.find(|candidate| {
+                apply(self, candidate);
+                self.is_failing()
+            });
+            if found.is_some() {
+                self.on_best();
+            }
+            return found;
+        };
+
+        let mut batch = Vec::with_capacity(jobs);
+        let mut inputs = Vec::with_capacity(jobs);
+
+        loop {
+            batch.clear();
+            inputs.clear();
+
+            // candidates that have already been executed don't count towards the batch
+            let mut pending = 0;
+            for candidate in candidates.by_ref() {
+                apply(self, &candidate);
+                let input = &self.input.as_ref()[..self.end];
+                let key = cache_key(input);
+                let cached = self.cache.get(&key).copied();
+                if let Some(outcome) = cached {
+                    self.cache_hits += 1;
+                    batch.push((candidate, key, Some(outcome == Outcome::Fail)));
+                } else {
+                    inputs.push(input.to_vec());
+                    batch.push((candidate, key, None));
+                    pending += 1;
+                    if pending == jobs {
+                        break;
+                    }
+                }
+            }
+
+            if batch.is_empty() {
+                return None;
+            }
+
+            let mut results = std::thread::scope(|scope| {
+                let workers: Vec<_> = inputs
+                    .iter()
+                    .map(|input| {
+                        scope.spawn(move || {
+                            panic::forward_panic(false);
+                            executor.is_failing(input)
+                        })
+                    })
+                    .collect();
+                workers
+                    .into_iter()
+                    .map(|worker| worker.join().unwrap_or(false))
+                    .collect::<Vec<_>>()
+            })
+            .into_iter();
+            self.executions += inputs.len();
+
+            self.print_progress();
+
+            for (candidate, key, cached) in batch.drain(..) {
+                let is_failing = match cached {
+                    Some(is_failing) => is_failing,
+                    None => {
+                        let is_failing = results.next().unwrap_or(false);
+                        // failures are confirmed below so only passes are cached
+                        if !is_failing {
+                            self.cache.insert(key, Outcome::Pass);
+                        }
+                        is_failing
+                    }
+                };
+
+                if !is_failing {
+                    continue;
+                }
+
+                // the executor could disagree with the test if the test isn't deterministic
+                apply(self, &candidate);
+                if self.is_failing() {
+                    self.on_best();
+                    return Some(candidate);
+                }
+            }
+        }
+    }
+
+    /// Records the current input as the smallest failing input found so far
+    fn on_best(&mut self) {
+        self.improvements.push(Improvement {
+            pass: self.pass.to_string(),
+            len: self.end,
+        });
+        self.best.clear();
+        self.best
+            .extend_from_slice(&self.input.as_ref()[..self.end]);
+
+        if let Some(on_best) = self.hooks.on_best.as_mut() {
+            on_best(&self.best);
+        }
+    }
+
+    fn print_progress(&mut self) {
+        if self.last_progress.elapsed() < PROGRESS_INTERVAL {
+            return;
+        }
+        self.last_progress = Instant::now();
+
+        let mut best: String = self
+            .best
+            .iter()
+            .take(32)
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        if self.best.len() > 32 {
+            best.push_str("...");
+        }
+
+        eprintln!(
+            "[bolero] shrinking for {}s: {} bytes, {} passes applied, best input: {best}",
+            self.start_time.elapsed().as_secs(),
+            self.best.len(),
+            self.improvements.len(),
+        );
+    }
+
+    /// Returns `true` if the test fails on the current input
+    ///
+    /// The outcome of each input is cached so transforms that produce the same candidate more
+    /// than once only execute the test the first time.
+    fn is_failing(&mut self) -> bool {
+        let key = cache_key(&self.input.as_ref()[..self.end]);
+        if let Some(outcome) = self.cache.get(&key) {
+            self.cache_hits += 1;
+            return *outcome == Outcome::Fail;
+        }
+
+        let outcome = match self.execute() {
+            Ok(true) => Outcome::Pass,
+            Ok(false) => Outcome::Invalid,
+            Err(_) => Outcome::Fail,
+        };
+        self.executions += 1;
+        self.cache.insert(key, outcome);
+        outcome == Outcome::Fail
+    }
+
     fn execute(&mut self) -> Result<bool, PanicError> {
-        self.test.test(&mut ShrinkInput {
+        let result = self.test.test(&mut ShrinkInput {
             input: &self.input,
             len: self.end,
             options: self.options,
-        })
+        });
+        self.print_progress();
+        result
     }
 
     fn generate_value(&mut self) -> T::Value {
@@ -323,6 +877,21 @@ impl<'a, T: Test, I: Input> Shrinker<'a, T, I> {
     }
 }
 
+/// The outcome of executing the test on a candidate
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Outcome {
+    Pass,
+    /// The input was rejected by the test
+    Invalid,
+    Fail,
+}
+
+fn cache_key(input: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    input.hash(&mut hasher);
+    hasher.finish()
+}
+
 struct ShrinkInput<'a, I: Input> {
     input: &'a I,
     options: &'a driver::Options,
@@ -333,7 +902,7 @@ impl<'a, I: Input, Output> crate::Input<Output> for ShrinkInput<'a, I> {
     type Driver = I::Driver<'a>;
 
     fn with_slice<F: FnMut(&[u8]) -> Output>(&mut self, f: &mut F) -> Output {
-        f(self.input.as_ref())
+        f(&self.input.as_ref()[..self.len])
     }
 
     fn with_driver<F: FnMut(&mut Self::Driver) -> Output>(&mut self, f: &mut F) -> Output {
@@ -341,3 +910,24 @@ impl<'a, I: Input, Output> crate::Input<Output> for ShrinkInput<'a, I> {
         f(&mut driver)
     }
 }
+
+struct RecordInput<'a> {
+    input: &'a [u8],
+    options: &'a driver::Options,
+    spans: Vec<structure::Span>,
+}
+
+impl<'a, Output> crate::Input<Output> for RecordInput<'a> {
+    type Driver = structure::Recorder<'a>;
+
+    fn with_slice<F: FnMut(&[u8]) -> Output>(&mut self, f: &mut F) -> Output {
+        f(self.input)
+    }
+
+    fn with_driver<F: FnMut(&mut Self::Driver) -> Output>(&mut self, f: &mut F) -> Output {
+        let mut driver = structure::Recorder::new(self.input, self.options);
+        let output = f(&mut driver);
+        self.spans = driver.into_spans();
+        output
+    }
+}
@@ -0,0 +1,366 @@
+//! Records the structure of a generated value
+//!
+//! The byte slice driver gives no indication of which bytes belong to which part of a value. By
+//! wrapping it in a [`Recorder`], the `enter_product`, `enter_sum` and `enter_list` boundaries
+//! reported by the generators are captured as byte spans, which lets the shrinker delete whole
+//! list elements, replace sum variants with their base case, and minimise individual values.
+
+use bolero_generator::{
+    driver::{self, ByteSliceDriver, Driver},
+    ValueGenerator,
+};
+use core::ops::Bound;
+
+/// The input used to measure the fields of a base case
+static ZEROS: [u8; 1024] = [0; 1024];
+
+#[derive(Clone, Debug)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub parent: Option<usize>,
+    pub kind: Kind,
+}
+
+#[derive(Clone, Debug)]
+pub enum Kind {
+    Product,
+    Sum {
+        /// The end of the bytes that selected the variant
+        variant_end: usize,
+        /// The bytes that select the base case, if a different variant was selected
+        base_case: Option<Vec<u8>>,
+        /// The number of bytes the base case consumes when its fields are all zero
+        base_case_fields: usize,
+    },
+    List {
+        len: usize,
+        /// The end of the bytes that produced the length
+        len_end: usize,
+        /// The bytes that produce `len - 1`, if they could be found
+        shorter: Option<Vec<u8>>,
+    },
+    /// A primitive value, which is minimised by zeroing its bytes
+    Leaf,
+}
+
+/// A driver that records the spans of each generated value
+pub struct Recorder<'a> {
+    inner: ByteSliceDriver<'a>,
+    options: &'a driver::Options,
+    len: usize,
+    parent: Option<usize>,
+    spans: Vec<Span>,
+}
+
+impl<'a> Recorder<'a> {
+    pub fn new(input: &'a [u8], options: &'a driver::Options) -> Self {
+        let inner = ByteSliceDriver::new(input, options);
+        let len = inner.as_slice().len();
+        Self {
+            inner,
+            options,
+            len,
+            parent: None,
+            spans: vec![],
+        }
+    }
+
+    pub fn into_spans(self) -> Vec<Span> {
+        self.spans
+    }
+
+    #[inline]
+    fn offset(&self) -> usize {
+        self.len - self.inner.as_slice().len()
+    }
+
+    fn enter<R, F: FnOnce(&mut Self, usize) -> Option<R>>(
+        &mut self,
+        kind: Kind,
+        f: F,
+    ) -> Option<R> {
+        let index = self.spans.len();
+        let start = self.offset();
+        self.spans.push(Span {
+            start,
+            end: start,
+            parent: self.parent,
+            kind,
+        });
+
+        let parent = self.parent.replace(index);
+        let value = f(self, index);
+        self.parent = parent;
+        self.spans[index].end = self.offset();
+
+        value
+    }
+}
+
+/// Searches for the `width` bytes that `decode` into `target`
+///
+/// The byte slice driver scales little-endian values into the requested range, which means the
+/// decoded value never decreases as the encoded value increases, so a binary search finds the
+/// smallest encoding.
+fn encode<T: PartialOrd>(
+    width: usize,
+    target: T,
+    mut decode: impl FnMut(&[u8]) -> Option<T>,
+) -> Option<Vec<u8>> {
+    if width == 0 || width > 16 {
+        return None;
+    }
+
+    let to_bytes = |value: u128| value.to_le_bytes()[..width].to_vec();
+    let (mut low, mut high) = (0u128, u128::MAX >> (128 - width * 8));
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if decode(&to_bytes(mid))? < target {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+
+    let bytes = to_bytes(low);
+    (decode(&bytes)? == target).then_some(bytes)
+}
+
+macro_rules! leaf {
+    ($name:ident, $ty:ty) => {
+        #[inline]
+        fn $name(&mut self, min: Bound<&$ty>, max: Bound<&$ty>) -> Option<$ty> {
+            self.enter(Kind::Leaf, |driver, _| driver.inner.$name(min, max))
+        }
+    };
+}
+
+impl Driver for Recorder<'_> {
+    #[inline]
+    fn depth(&self) -> usize {
+        self.inner.depth()
+    }
+
+    #[inline]
+    fn set_depth(&mut self, depth: usize) {
+        self.inner.set_depth(depth)
+    }
+
+    #[inline]
+    fn max_depth(&self) -> usize {
+        self.inner.max_depth()
+    }
+
+    fn enter_product<Output, F, Ret>(&mut self, mut f: F) -> Option<Ret>
+    where
+        Output: 'static,
+        F: FnMut(&mut Self) -> Option<Ret>,
+    {
+        self.enter(Kind::Product, |driver, _| f(driver))
+    }
+
+    fn enter_sum<Output, F, Ret>(
+        &mut self,
+        element_names: Option<&'static [&'static str]>,
+        elements: usize,
+        base_case: usize,
+        mut f: F,
+    ) -> Option<Ret>
+    where
+        Output: 'static,
+        F: FnMut(&mut Self, usize) -> Option<Ret>,
+    {
+        let _ = element_names;
+        let kind = Kind::Sum {
+            variant_end: 0,
+            base_case: None,
+            base_case_fields: 0,
+        };
+
+        self.enter(kind, |driver, index| {
+            let start = driver.offset();
+            let idx = driver.inner.gen_variant(elements, base_case)?;
+            let variant_end = driver.offset();
+
+            let options = driver.options;
+            let encoded = if idx == base_case {
+                None
+            } else {
+                encode(variant_end - start, base_case, |bytes| {
+                    ByteSliceDriver::new(bytes, options).gen_variant(elements, base_case)
+                })
+            };
+
+            let base_case_fields = if encoded.is_some() {
+                let mut zeros = Recorder::new(&ZEROS, options);
+                zeros.set_depth(driver.depth());
+                let _ = f(&mut zeros, base_case);
+                zeros.offset()
+            } else {
+                0
+            };
+
+            driver.spans[index].kind = Kind::Sum {
+                variant_end,
+                base_case: encoded,
+                base_case_fields,
+            };
+
+            f(driver, idx)
+        })
+    }
+
+    fn enter_list<Output, F, Len, Ret>(&mut self, lens: &Len, mut f: F) -> Option<Ret>
+    where
+        Output: 'static,
+        F: FnMut(&mut Self, usize) -> Option<Ret>,
+        Len: ValueGenerator<Output = usize>,
+    {
+        self.depth_guard(|driver| {
+            let kind = Kind::List {
+                len: 0,
+                len_end: 0,
+                shorter: None,
+            };
+
+            driver.enter(kind, |driver, index| {
+                let start = driver.offset();
+                let len = lens.generate(driver)?;
+                let len_end = driver.offset();
+
+                let options = driver.options;
+                let shorter = len.checked_sub(1).and_then(|target| {
+                    encode(len_end - start, target, |bytes| {
+                        lens.generate(&mut ByteSliceDriver::new(bytes, options))
+                    })
+                });
+
+                driver.spans[index].kind = Kind::List {
+                    len,
+                    len_end,
+                    shorter,
+                };
+
+                f(driver, len)
+            })
+        })
+    }
+
+    #[inline]
+    fn gen_variant(&mut self, variants: usize, base_case: usize) -> Option<usize> {
+        self.inner.gen_variant(variants, base_case)
+    }
+
+    leaf!(gen_u8, u8);
+    leaf!(gen_i8, i8);
+    leaf!(gen_u16, u16);
+    leaf!(gen_i16, i16);
+    leaf!(gen_u32, u32);
+    leaf!(gen_i32, i32);
+    leaf!(gen_u64, u64);
+    leaf!(gen_i64, i64);
+    leaf!(gen_u128, u128);
+    leaf!(gen_i128, i128);
+    leaf!(gen_usize, usize);
+    leaf!(gen_isize, isize);
+    leaf!(gen_f32, f32);
+    leaf!(gen_f64, f64);
+    leaf!(gen_char, char);
+
+    #[inline]
+    fn gen_bool(&mut self, probability: Option<f32>) -> Option<bool> {
+        self.enter(Kind::Leaf, |driver, _| driver.inner.gen_bool(probability))
+    }
+
+    #[inline]
+    fn gen_from_bytes<Hint, Gen, T>(&mut self, hint: Hint, produce: Gen) -> Option<T>
+    where
+        Hint: FnOnce() -> (usize, Option<usize>),
+        Gen: FnMut(&[u8]) -> Option<(usize, T)>,
+    {
+        self.enter(Kind::Leaf, |driver, _| {
+            driver.inner.gen_from_bytes(hint, produce)
+        })
+    }
+}
+
+/// Returns the candidate inputs for a recorded structure, ordered from the most to the least
+/// aggressive transform
+pub fn candidates<'a>(input: &'a [u8], spans: &'a [Span]) -> impl Iterator<Item = Vec<u8>> + 'a {
+    let splice = move |start: usize, end: usize, bytes: &[&[u8]]| {
+        let mut out = input[..start].to_vec();
+        for bytes in bytes {
+            out.extend_from_slice(bytes);
+        }
+        out.extend_from_slice(&input[end..]);
+        out
+    };
+
+    let elements = spans.iter().enumerate().flat_map(move |(index, span)| {
+        let Kind::List {
+            len,
+            len_end,
+            shorter: Some(shorter),
+        } = &span.kind
+        else {
+            return vec![];
+        };
+
+        let children: Vec<_> = spans
+            .iter()
+            .filter(|child| child.parent == Some(index) && child.start >= *len_end)
+            .collect();
+
+        // each element needs its own span for it to be removed
+        if children.len() != *len {
+            return vec![];
+        }
+
+        // remove the later elements first, since they're usually the least interesting
+        children
+            .iter()
+            .rev()
+            .map(|child| {
+                splice(
+                    span.start,
+                    child.end,
+                    &[shorter, &input[*len_end..child.start]],
+                )
+            })
+            .collect()
+    });
+
+    let variants = spans.iter().flat_map(move |span| {
+        let Kind::Sum {
+            variant_end,
+            base_case: Some(base_case),
+            base_case_fields,
+        } = &span.kind
+        else {
+            return vec![];
+        };
+
+        let fields = vec![0; *base_case_fields];
+
+        vec![
+            // replace the fields of the previous variant with the smallest base case fields
+            splice(span.start, span.end, &[base_case, &fields]),
+            // reuse the fields of the previous variant
+            splice(span.start, *variant_end, &[base_case]),
+        ]
+    });
+
+    let leaves = spans.iter().filter_map(move |span| {
+        let bytes = &input[span.start..span.end];
+        if !matches!(span.kind, Kind::Leaf) || bytes.iter().all(|b| *b == 0) {
+            return None;
+        }
+
+        let zeros = vec![0; bytes.len()];
+        Some(splice(span.start, span.end, &[&zeros]))
+    });
+
+    elements.chain(variants).chain(leaves)
+}
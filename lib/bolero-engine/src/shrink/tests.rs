@@ -1,5 +1,8 @@
 use super::*;
-use std::time::Duration;
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
 
 macro_rules! shrink_test_assert {
     ($test:ident, $input:ident, $options:ident, (None)) => {
@@ -106,3 +109,155 @@ shrink_test!(
         }
     }
 );
+
+shrink_test!(
+    variant_shrink_test,
+    produce::<Vec<Result<u64, (u8, Option<u16>)>>>(),
+    [255u8; 256],
+    Duration::from_secs(1),
+    (vec![Ok(0), Ok(0), Err((0, Some(0)))]),
+    |value: Vec<Result<u64, (u8, Option<u16>)>>| {
+        assert!(!value.iter().skip(2).any(|v| matches!(v, Err((_, Some(_))))));
+    }
+);
+
+shrink_test!(
+    nested_vec_shrink_test,
+    produce::<Vec<Vec<Option<u32>>>>(),
+    [255u8; 256],
+    Duration::from_secs(1),
+    (vec![vec![Some(0), Some(0), Some(0)]]),
+    |value: Vec<Vec<Option<u32>>>| {
+        assert!(value.iter().flatten().filter(|v| v.is_some()).count() < 3);
+    }
+);
+
+shrink_test!(
+    field_shrink_test,
+    produce::<Vec<Option<(u64, u64)>>>(),
+    [255u8; 256],
+    Duration::from_secs(1),
+    (vec![Some((0, 1)), Some((0, 1))]),
+    |value: Vec<Option<(u64, u64)>>| {
+        assert!(value.iter().flatten().filter(|v| v.1 > 0).count() < 2);
+    }
+);
+
+#[test]
+fn leaf_search_lower_bound_test() {
+    use bolero_generator::produce;
+
+    panic::set_hook();
+    panic::forward_panic(false);
+
+    // every value above the range fails, so bisecting alone stops at 1001
+    let check = |value: u32| assert!((1..=1000).contains(&value));
+    let mut test = crate::ClonedGeneratorTest::new(check, produce::<u32>());
+    let options = driver::Options::default();
+    let mut shrinker = Shrinker::new(&mut test, vec![255u8; 4], None, &options);
+    let _ = shrinker.apply_leaf_search(Instant::now(), Duration::from_secs(1));
+
+    assert_eq!(shrinker.input, [0; 4]);
+}
+
+#[test]
+fn parallel_shrink_test() {
+    use bolero_generator::produce;
+
+    let check = |value: Vec<u32>| {
+        assert!(value.len() < 3 || value[1] < 5);
+    };
+
+    let options = driver::Options::default()
+        .with_shrink_time(Duration::from_secs(1))
+        .with_shrink_jobs(4);
+
+    // each worker runs its own instance of the test
+    let executor = |input: &[u8]| {
+        let mut test = crate::ClonedGeneratorTest::new(check, produce::<Vec<u32>>());
+        let bytes = input.to_vec();
+        test.test(&mut ShrinkInput {
+            input: &bytes,
+            len: bytes.len(),
+            options: &options,
+        })
+        .is_err()
+    };
+
+    let mut best = vec![];
+    let mut on_best = |input: &[u8]| best = input.to_vec();
+
+    let mut test = crate::ClonedGeneratorTest::new(check, produce::<Vec<u32>>());
+    let mut input = vec![255u8; 256];
+    let hooks = Hooks::default()
+        .with_executor(&executor)
+        .with_on_best(&mut on_best);
+    let failure =
+        shrink_with(&mut test, &mut input, None, &options, hooks).expect("should produce a result");
+
+    assert_eq!(failure.input, vec![0, 5, 0]);
+    // the last improvement is the shrunken input
+    assert_eq!(best, input);
+}
+
+#[test]
+fn custom_pass_test() {
+    struct Canonical;
+
+    impl ShrinkPass for Canonical {
+        fn name(&self) -> &str {
+            "canonical packet"
+        }
+
+        fn candidates(&self, _input: &[u8]) -> Vec<Vec<u8>> {
+            vec![vec![1, 0, 0, 1]]
+        }
+    }
+
+    panic::forward_panic(true);
+
+    // the last byte is a checksum, which the byte-level passes can't keep valid
+    let mut test = |bytes: &[u8]| {
+        if bytes.len() >= 4 && bytes[3] == bytes[0] ^ bytes[1] ^ bytes[2] {
+            assert_eq!(bytes[0], 0);
+        }
+    };
+
+    let options = driver::Options::default().with_shrink_time(Duration::from_secs(1));
+    let passes: Vec<Box<dyn ShrinkPass>> = vec![Box::new(Canonical)];
+    let hooks = Hooks::default().with_passes(&passes);
+
+    let mut input = vec![255, 9, 9, 255, 42];
+    let failure =
+        shrink_with(&mut test, &mut input, None, &options, hooks).expect("should produce a result");
+
+    assert_eq!(input, [1, 0, 0, 1]);
+    assert!(failure.to_string().contains("Shrunk by: "));
+    let report = failure.shrink.expect("should report the passes");
+    assert!(report
+        .improvements
+        .iter()
+        .any(|improvement| improvement.pass == "canonical packet" && improvement.len == 4));
+}
+
+#[test]
+fn cache_test() {
+    static EXECUTIONS: AtomicUsize = AtomicUsize::new(0);
+
+    panic::forward_panic(true);
+
+    let mut test = |bytes: &[u8]| {
+        EXECUTIONS.fetch_add(1, Ordering::Relaxed);
+        assert!(bytes.len() < 3);
+    };
+
+    let options = driver::Options::default().with_shrink_time(Duration::from_secs(1));
+    let mut input = vec![255; 16];
+    let failure = shrink(&mut test, &mut input, None, &options).expect("should produce a result");
+
+    assert_eq!(input, [0, 0, 0]);
+    let report = failure.shrink.expect("should report the executions");
+    // the truncation pass retries the same prefixes on each iteration
+    assert!(report.cache_hits > 0);
+    assert!(report.executions <= EXECUTIONS.load(Ordering::Relaxed));
+}
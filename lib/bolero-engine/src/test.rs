@@ -29,6 +29,56 @@ pub trait Test: Sized {
     ) -> Option<Failure<Self::Value>> {
         crate::shrink::shrink(self, input, seed, options)
     }
+
+    /// Returns a function that creates copies of the test, so shrinking candidates can be
+    /// evaluated on other threads
+    ///
+    /// Tests aren't required to be thread-safe, so this returns `None` unless the test is wrapped
+    /// in a [`SharedTest`].
+    fn fork<'a>(&self) -> Option<Fork<'a, Self>>
+    where
+        Self: 'a,
+    {
+        None
+    }
+}
+
+/// Creates copies of a test for other threads
+pub type Fork<'a, T> = Box<dyn Fn() -> T + Send + Sync + 'a>;
+
+/// A test that can be copied to other threads while shrinking its failures
+#[derive(Clone)]
+pub struct SharedTest<T>(T);
+
+impl<T> SharedTest<T> {
+    pub fn new(test: T) -> Self {
+        Self(test)
+    }
+}
+
+impl<T: Test + Clone + Send + Sync> Test for SharedTest<T> {
+    type Value = T::Value;
+
+    #[inline]
+    fn test<I: Input<Result<bool, PanicError>>>(
+        &mut self,
+        input: &mut I,
+    ) -> Result<bool, PanicError> {
+        self.0.test(input)
+    }
+
+    #[inline]
+    fn generate_value<I: Input<Self::Value>>(&self, input: &mut I) -> Self::Value {
+        self.0.generate_value(input)
+    }
+
+    fn fork<'a>(&self) -> Option<Fork<'a, Self>>
+    where
+        Self: 'a,
+    {
+        let test = self.clone();
+        Some(Box::new(move || test.clone()))
+    }
 }
 
 impl<F: RefUnwindSafe + FnMut(&[u8]) -> Ret, Ret> Test for F
@@ -59,6 +109,7 @@ where
     }
 }
 
+#[derive(Clone)]
 pub struct BorrowedSliceTest<F> {
     fun: F,
 }
@@ -97,6 +148,7 @@ where
     }
 }
 
+#[derive(Clone)]
 pub struct ClonedSliceTest<F> {
     fun: F,
 }
@@ -184,6 +236,13 @@ impl<F, G, V> BorrowedGeneratorTest<F, G, V> {
     }
 }
 
+impl<F: Clone, G: Clone, V> Clone for BorrowedGeneratorTest<F, G, V> {
+    fn clone(&self) -> Self {
+        // the cached value is regenerated by each copy
+        Self::new(self.fun.clone(), self.produce.clone())
+    }
+}
+
 impl<F: RefUnwindSafe + FnMut(&G::Output) -> Ret, G: ValueGenerator, Ret> Test
     for BorrowedGeneratorTest<F, G, G::Output>
 where
@@ -240,6 +299,13 @@ impl<F, G, V> ClonedGeneratorTest<F, G, V> {
     }
 }
 
+impl<F: Clone, G: Clone, V> Clone for ClonedGeneratorTest<F, G, V> {
+    fn clone(&self) -> Self {
+        // the cached value is regenerated by each copy
+        Self::new(self.fun.clone(), self.produce.clone())
+    }
+}
+
 impl<F: RefUnwindSafe + FnMut(G::Output) -> Ret, G: ValueGenerator, Ret> Test
     for ClonedGeneratorTest<F, G, G::Output>
 where
@@ -31,6 +31,7 @@ impl<G: 'static + ValueGenerator> Any for G {
 }
 
 #[inline]
+#[track_caller]
 pub fn any<T: TypeGenerator>() -> T {
     produce().any()
 }
@@ -41,6 +42,7 @@ pub trait AnySliceExt<T> {
 
 impl<T> AnySliceExt<T> for [T] {
     #[inline]
+    #[track_caller]
     fn pick(&self) -> &T {
         let index = (0..self.len()).any();
         &self[index]
@@ -56,6 +58,7 @@ pub trait AnySliceMutExt<T> {
 
 impl<T> AnySliceMutExt<T> for [T] {
     #[inline]
+    #[track_caller]
     fn shuffle(&mut self) {
         let max_dst = self.len().saturating_sub(1);
         for src in 0..max_dst {
@@ -65,6 +68,7 @@ impl<T> AnySliceMutExt<T> for [T] {
     }
 
     #[inline]
+    #[track_caller]
     fn fill_any(&mut self)
     where
         T: TypeGenerator,
@@ -78,6 +82,7 @@ impl<T> AnySliceMutExt<T> for [T] {
 #[cfg(feature = "alloc")]
 impl<T> AnySliceMutExt<T> for alloc::collections::VecDeque<T> {
     #[inline]
+    #[track_caller]
     fn shuffle(&mut self) {
         let max_dst = self.len().saturating_sub(1);
         for src in 0..max_dst {
@@ -87,6 +92,7 @@ impl<T> AnySliceMutExt<T> for alloc::collections::VecDeque<T> {
     }
 
     #[inline]
+    #[track_caller]
     fn fill_any(&mut self)
     where
         T: TypeGenerator,
@@ -98,6 +104,7 @@ impl<T> AnySliceMutExt<T> for alloc::collections::VecDeque<T> {
 }
 
 #[inline]
+#[track_caller]
 pub fn fill<T>(values: &mut [T])
 where
     T: TypeGenerator,
@@ -106,11 +113,13 @@ where
 }
 
 #[inline]
+#[track_caller]
 pub fn shuffle<T>(items: &mut [T]) {
     items.shuffle()
 }
 
 #[inline]
+#[track_caller]
 pub fn pick<T>(items: &[T]) -> &T {
     items.pick()
 }
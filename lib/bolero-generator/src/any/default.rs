@@ -1,16 +1,17 @@
 use crate::driver::object::{self, DynDriver, Object};
-use core::fmt;
+use crate::rejection::Rejection;
+use core::{fmt, panic::Location};
 use std::cell::RefCell;
 
 pub trait Scope: 'static + DynDriver + core::any::Any {
-    fn borrowed(&mut self) -> object::Borrowed;
+    fn borrowed(&mut self) -> object::Borrowed<'_>;
 }
 
 impl<T> Scope for T
 where
     T: 'static + DynDriver + core::any::Any,
 {
-    fn borrowed(&mut self) -> object::Borrowed {
+    fn borrowed(&mut self) -> object::Borrowed<'_> {
         object::Borrowed(self)
     }
 }
@@ -19,6 +20,7 @@ type Type = Box<dyn Scope>;
 
 thread_local! {
     static SCOPE: RefCell<Type> = RefCell::new(Box::new(Object(default())));
+    static DRAWS: RefCell<Option<Vec<Draw>>> = const { RefCell::new(None) };
 }
 
 fn default() -> impl crate::Driver {
@@ -85,22 +87,113 @@ fn borrow_with<F: FnOnce(&mut object::Borrowed) -> R, R>(f: F) -> R {
 
 #[track_caller]
 pub fn any<G: crate::ValueGenerator>(g: &G) -> G::Output {
-    borrow_with(|driver| {
+    // closures don't track the caller so capture it up front
+    let location = Location::caller();
+
+    let value = borrow_with(|driver| {
         g.generate(driver).unwrap_or_else(|| {
+            // prefer the reason recorded by a rejecting combinator, if any
+            crate::rejection::record_fallback(Rejection {
+                by: "any",
+                message: core::any::type_name::<G>(),
+                location,
+            });
             std::panic::panic_any(Error {
-                location: core::panic::Location::caller(),
+                location,
                 generator: core::any::type_name::<G>(),
                 output: core::any::type_name::<G::Output>(),
             })
         })
-    })
+    });
+
+    DRAWS.with(|draws| {
+        if let Some(draws) = draws.borrow_mut().as_mut() {
+            draws.push(Draw {
+                location,
+                value: describe(&value),
+            });
+        }
+    });
+
+    value
+}
+
+/// Formats a drawn value, which isn't required to implement `Debug`
+///
+/// Common types are formatted with `Debug` and anything else is recorded as `<type name>`.
+fn describe<T: 'static>(value: &T) -> String {
+    let value: &dyn core::any::Any = value;
+
+    macro_rules! debug {
+        ($($ty:ty),* $(,)?) => {
+            $(
+                if let Some(value) = value.downcast_ref::<$ty>() {
+                    return format!("{value:?}");
+                }
+            )*
+        };
+    }
+
+    debug! {
+        u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize, f32, f64, bool, char, (),
+        String, &'static str, Vec<u8>,
+    }
+
+    format!("<{}>", core::any::type_name::<T>())
+}
+
+/// A value drawn with [`any`]
+#[derive(Clone, Debug)]
+pub struct Draw {
+    pub location: &'static Location<'static>,
+    pub value: String,
+}
+
+impl fmt::Display for Draw {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.location, self.value)
+    }
+}
+
+// protect against panics in the `record` function
+struct PrevDraws(Option<Option<Vec<Draw>>>);
+
+impl PrevDraws {
+    fn reset(mut self) -> Vec<Draw> {
+        let prev = self.0.take().unwrap();
+        DRAWS
+            .with(|draws| core::mem::replace(&mut *draws.borrow_mut(), prev))
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for PrevDraws {
+    fn drop(&mut self) {
+        if let Some(prev) = self.0.take() {
+            DRAWS.with(|draws| *draws.borrow_mut() = prev);
+        }
+    }
+}
+
+/// Records each value drawn with [`any`] while executing `f`
+pub fn record<F: FnOnce() -> R, R>(f: F) -> (R, Vec<Draw>) {
+    let prev = DRAWS.with(|draws| draws.borrow_mut().replace(vec![]));
+    let prev = PrevDraws(Some(prev));
+    let res = f();
+    (res, prev.reset())
 }
 
 #[track_caller]
 pub fn assume(condition: bool, message: &'static str) {
     if !condition {
+        let location = core::panic::Location::caller();
+        crate::rejection::record(Rejection {
+            by: "assume",
+            message,
+            location,
+        });
         std::panic::panic_any(Error {
-            location: core::panic::Location::caller(),
+            location,
             generator: "<assume>",
             output: message,
         });
@@ -70,6 +70,20 @@ pub fn fill_bytes(bytes: &mut [u8]) {
     }
 }
 
+/// A value drawn with [`any`]
+#[derive(Clone, Debug)]
+pub struct Draw {
+    pub location: &'static core::panic::Location<'static>,
+    pub value: alloc::string::String,
+}
+
+/// Records each value drawn with [`any`] while executing `f`
+///
+/// Values aren't recorded with kani so this always returns an empty list.
+pub fn record<F: FnOnce() -> R, R>(f: F) -> (R, alloc::vec::Vec<Draw>) {
+    (f(), alloc::vec::Vec::new())
+}
+
 #[inline]
 pub fn assume(condition: bool, message: &'static str) {
     #[cfg(kani)]
@@ -44,3 +44,27 @@ fn fill_any_test() {
 fn pick_test() {
     exhaustive("pick_test", || [1, 2, 3].pick())
 }
+
+#[test]
+fn record_test() {
+    let ((a, b), draws) = scope::record(|| ((0..4).any(), any::<bool>()));
+
+    let values: Vec<_> = draws.iter().map(|draw| draw.value.as_str()).collect();
+    assert_eq!(values, [a.to_string(), b.to_string()]);
+
+    for draw in &draws {
+        assert_eq!(draw.location.file(), file!());
+    }
+
+    // values that don't implement `Debug` are recorded by their type
+    struct Opaque;
+    let (_, draws) = scope::record(|| (0..4).map_gen(|_| Opaque).any());
+    assert_eq!(
+        draws[0].value,
+        format!("<{}>", core::any::type_name::<Opaque>())
+    );
+
+    // nothing is recorded outside of the scope
+    let (_, draws) = scope::record(|| ());
+    assert!(draws.is_empty());
+}
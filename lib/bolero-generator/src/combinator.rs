@@ -1,4 +1,8 @@
-use crate::{Driver, ValueGenerator};
+use crate::{
+    rejection::{self, Rejection},
+    Driver, ValueGenerator,
+};
+use core::panic::Location;
 
 #[derive(Clone, Debug)]
 pub struct MapGenerator<Generator, Map> {
@@ -52,6 +56,7 @@ fn and_then_test() {
 pub struct FilterGenerator<Generator, Filter> {
     pub(crate) generator: Generator,
     pub(crate) filter: Filter,
+    pub(crate) location: &'static Location<'static>,
 }
 
 impl<G: ValueGenerator, F: Fn(&G::Output) -> bool> ValueGenerator for FilterGenerator<G, F> {
@@ -63,6 +68,11 @@ impl<G: ValueGenerator, F: Fn(&G::Output) -> bool> ValueGenerator for FilterGene
             if (self.filter)(&value) {
                 Some(value)
             } else {
+                rejection::record(Rejection {
+                    by: "filter",
+                    message: "",
+                    location: self.location,
+                });
                 None
             }
         })
@@ -78,6 +88,7 @@ fn filter_test() {
 pub struct FilterMapGenerator<Generator, FilterMap> {
     pub(crate) generator: Generator,
     pub(crate) filter_map: FilterMap,
+    pub(crate) location: &'static Location<'static>,
 }
 
 impl<G: ValueGenerator, F: Fn(G::Output) -> Option<T>, T: 'static> ValueGenerator
@@ -88,7 +99,15 @@ impl<G: ValueGenerator, F: Fn(G::Output) -> Option<T>, T: 'static> ValueGenerato
     fn generate<D: Driver>(&self, driver: &mut D) -> Option<Self::Output> {
         driver.enter_combinator::<Self::Output, _, _>(|driver| {
             let value = self.generator.generate(driver)?;
-            (self.filter_map)(value)
+            let value = (self.filter_map)(value);
+            if value.is_none() {
+                rejection::record(Rejection {
+                    by: "filter_map",
+                    message: "",
+                    location: self.location,
+                });
+            }
+            value
         })
     }
 }
@@ -3,15 +3,47 @@ use crate::{
     bounded::BoundExt,
     uniform::{self, Uniform},
 };
-use alloc::vec::Vec;
+use alloc::{
+    collections::{BTreeMap, VecDeque},
+    vec::Vec,
+};
 use core::ops::{Bound, ControlFlow};
 
 #[derive(Clone, Debug)]
 pub struct Driver {
     state: State,
-    depth: usize,
     max_depth: usize,
     buffer: Buffer,
+    shard: Option<Shard>,
+}
+
+/// Splits the enumeration between multiple workers
+///
+/// The states are grouped by their first few frames, which are called a prefix, and each prefix
+/// is assigned to a worker in round-robin order. Every worker still runs the first state of each
+/// prefix, since that's the only way to discover the bounds of the prefix frames, but it skips the
+/// remaining states of prefixes owned by other workers. Only the owner counts the first state
+/// towards its progress, see [`Driver::is_owned`].
+///
+/// Size tiers don't enumerate the prefixes in order, so they're assigned by a hash of their frames
+/// instead.
+#[derive(Clone, Copy, Debug)]
+struct Shard {
+    index: usize,
+    count: usize,
+    /// The number of frames in a prefix, which is chosen after the first state is run
+    depth: Option<usize>,
+    /// The ordinal of the current prefix, or its hash with size tiers
+    prefix: usize,
+}
+
+/// The minimum number of prefixes for each worker, which evens out the size of each shard
+const PREFIXES_PER_SHARD: u64 = 16;
+
+impl Shard {
+    fn is_owned(&self) -> bool {
+        self.prefix % self.count == self.index
+    }
 }
 
 impl Default for Driver {
@@ -23,10 +55,43 @@ impl Default for Driver {
 impl Driver {
     pub fn new(options: &super::Options) -> Self {
         Self {
-            depth: 0,
             max_depth: options.max_depth_or_default(),
-            state: State::default(),
+            state: State {
+                tier: options.size_tiers().then(Tier::new),
+                ..Default::default()
+            },
             buffer: Buffer::default(),
+            shard: None,
+        }
+    }
+
+    /// Only enumerates the states assigned to the worker at `index` out of `count` workers
+    ///
+    /// Each worker should be configured with the same `count` and, when resuming, the same state.
+    pub fn set_shard(&mut self, index: usize, count: usize) {
+        assert!(
+            index < count,
+            "shard index {index} is out of bounds for {count}"
+        );
+        self.shard = Some(Shard {
+            index,
+            count,
+            depth: None,
+            prefix: 0,
+        });
+    }
+
+    /// Returns the prefix depth and ordinal of the current shard, which are needed to resume it
+    pub fn shard_position(&self) -> Option<(usize, usize)> {
+        let shard = self.shard?;
+        Some((shard.depth?, shard.prefix))
+    }
+
+    /// Restores the position of a shard that's being resumed
+    pub fn set_shard_position(&mut self, depth: usize, prefix: usize) {
+        if let Some(shard) = self.shard.as_mut() {
+            shard.depth = Some(depth);
+            shard.prefix = prefix;
         }
     }
 
@@ -38,23 +103,257 @@ impl Driver {
         self.state.deserialize(state);
     }
 
+    /// Resumes the enumeration from a serialized state
+    ///
+    /// The next call to [`Self::step`] selects the given state, after which the enumeration
+    /// continues with the states that follow it. With size tiers, those are the states that are
+    /// reached by incrementing its frames, which are enumerated without a size limit since the
+    /// smaller tiers aren't covered anyway.
+    pub fn resume(&mut self, state: &[u64]) {
+        self.state.deserialize(state);
+        self.state.started = false;
+
+        if let Some(tier) = self.state.tier.as_mut() {
+            *tier = Tier {
+                partial: true,
+                ..Tier::default()
+            };
+            tier.push(Pending {
+                size: state
+                    .iter()
+                    .fold(0, |size, value| size.saturating_add(*value)),
+                floor: 0,
+                frames: state.to_vec(),
+            });
+        }
+    }
+
+    /// Resumes the enumeration of a size tier from the states returned by [`Self::pending`]
+    pub fn resume_tier(&mut self, size: u64, pending: &[Pending]) {
+        let Some(tier) = self.state.tier.as_mut() else {
+            return;
+        };
+
+        *tier = Tier {
+            size,
+            complete: size.checked_sub(1),
+            ..Tier::default()
+        };
+        for pending in pending {
+            tier.push(pending.clone());
+        }
+        self.state.started = false;
+        self.state.estimate = pending.len() as f64;
+    }
+
+    /// Returns the maximum size of the states in the current tier
+    pub fn tier(&self) -> Option<u64> {
+        self.state.tier.as_ref().map(|tier| tier.size)
+    }
+
+    /// Returns the largest size tier that has been fully enumerated
+    ///
+    /// This is only available when the driver was configured with [`super::Options::with_size_tiers`].
+    pub fn complete_tier(&self) -> Option<u64> {
+        self.state.tier.as_ref().and_then(|tier| tier.complete)
+    }
+
+    /// Returns the states that still need to be enumerated by size, starting with the current one
+    ///
+    /// Resuming from these with [`Self::resume_tier`] runs the current state again, since its
+    /// bounds are needed to find the states that follow it.
+    pub fn pending(&self) -> Vec<Pending> {
+        let Some(tier) = self.state.tier.as_ref() else {
+            return Vec::new();
+        };
+
+        tier.current
+            .iter()
+            .chain(tier.queue.values().flatten())
+            .cloned()
+            .collect()
+    }
+
     pub fn estimate(&self) -> f64 {
         self.state.estimate()
     }
 
+    /// Returns `true` if the current state belongs to this worker's shard
+    ///
+    /// The first state of each prefix is run by every worker, but only belongs to one of them, so
+    /// counting the owned states of each worker adds up to the whole enumeration.
+    pub fn is_owned(&self) -> bool {
+        self.shard.map_or(true, |shard| shard.is_owned())
+    }
+
+    /// Returns `true` if the current state outgrew its size tier while it was being generated
+    ///
+    /// The generator fails as soon as that happens, so the state wasn't tested. It's tested in the
+    /// tier of its actual size instead.
+    pub fn is_deferred(&self) -> bool {
+        self.state.overflow.is_some()
+    }
+
     pub fn step(&mut self) -> ControlFlow<()> {
-        self.state.step()
+        if self.state.tier.is_some() {
+            return self.step_tier();
+        }
+
+        if !self.state.started {
+            self.state.started = true;
+            self.state.estimate = 1.0;
+            return ControlFlow::Continue(());
+        }
+
+        self.advance()
+    }
+
+    fn advance(&mut self) -> ControlFlow<()> {
+        let Some(shard) = self.shard.as_mut() else {
+            self.state.advance(usize::MAX)?;
+            return ControlFlow::Continue(());
+        };
+
+        let depth = *shard
+            .depth
+            .get_or_insert_with(|| self.state.prefix_depth(shard.count));
+
+        // skip the rest of the prefix if it belongs to another worker
+        let len = if shard.is_owned() { usize::MAX } else { depth };
+
+        let index = self.state.advance(len)?;
+        if index < depth {
+            shard.prefix += 1;
+        }
+
+        ControlFlow::Continue(())
+    }
+
+    fn step_tier(&mut self) -> ControlFlow<()> {
+        if self.state.started {
+            self.expand();
+        }
+        self.state.started = true;
+
+        loop {
+            let pending = self.state.tier.as_mut().unwrap().pop()?;
+
+            if let Some(shard) = self.shard.as_mut() {
+                shard.prefix = prefix_hash(&pending.frames, shard.depth);
+
+                // the rest of the prefix belongs to another worker
+                if !shard.is_owned() && shard.depth.map_or(false, |depth| pending.floor >= depth) {
+                    continue;
+                }
+            }
+
+            self.state.start(pending);
+            return ControlFlow::Continue(());
+        }
+    }
+
+    /// Queues the states that follow the one that was just run
+    ///
+    /// Incrementing any of the frames after the floor of a state leads to a new state, whose size
+    /// is at least one larger. The increments of frames before the floor are queued by the states
+    /// that came before it instead, so each state is only queued once.
+    fn expand(&mut self) {
+        let shard = self.shard.as_mut().map(|shard| {
+            let depth = *shard
+                .depth
+                .get_or_insert_with(|| self.state.prefix_depth(shard.count));
+            (*shard, depth)
+        });
+
+        let state = &mut self.state;
+        let tier = state.tier.as_mut().unwrap();
+        let floor = tier.floor;
+        let len = state
+            .overflow
+            .map_or(state.cursor, |(index, _)| index)
+            .min(state.stack.len());
+
+        let mut size = 0u64;
+        let mut deepest = 0usize;
+        for (index, frame) in state.stack[..len].iter().enumerate() {
+            let value = frame.value;
+            deepest = deepest.max(frame.depth);
+
+            if index >= floor && value < frame.bound {
+                let frames: Vec<_> = state.stack[..index]
+                    .iter()
+                    .map(|frame| frame.value)
+                    .chain(Some(value + 1))
+                    .collect();
+
+                let mut is_owned = true;
+                if let Some((shard, depth)) = shard {
+                    // the states that keep the prefix belong to the same worker
+                    if index >= depth && !shard.is_owned() {
+                        size = size.saturating_add(value);
+                        continue;
+                    }
+                    is_owned = prefix_hash(&frames, Some(depth)) % shard.count == shard.index;
+                }
+
+                if is_owned {
+                    state.estimate += 1.0;
+                }
+
+                tier.push(Pending {
+                    size: size.saturating_add(value + 1 + deepest as u64),
+                    floor: index,
+                    frames,
+                });
+            }
+
+            size = size.saturating_add(value);
+        }
+
+        // the state is tried again in the tier of the size it grew to
+        if let Some((index, size)) = state.overflow {
+            let is_owned = shard.map_or(true, |(shard, depth)| index < depth || shard.is_owned());
+            if is_owned {
+                tier.push(Pending {
+                    size,
+                    floor: index,
+                    frames: state.stack[..=index]
+                        .iter()
+                        .map(|frame| frame.value)
+                        .collect(),
+                });
+            }
+        }
     }
 
     pub fn replay(&mut self) {
-        self.state.cursor = 0;
+        self.state.restart();
     }
 }
 
+/// Returns a hash of the first `depth` frames of a state, which assigns its prefix to a worker
+///
+/// The frames that haven't been selected yet are zeros, which don't change the hash.
+fn prefix_hash(frames: &[u64], depth: Option<usize>) -> usize {
+    let mut hash = 0u64;
+    let mut factor = 1u64;
+    for value in frames.iter().take(depth.unwrap_or(usize::MAX)) {
+        factor = factor.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        hash = hash.wrapping_add(value.wrapping_mul(factor));
+    }
+
+    // mix the bits so the low ones depend on all of the frames
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    (hash ^ (hash >> 31)) as usize
+}
+
 #[derive(Clone, Copy, Debug, Default)]
 pub struct Frame {
     value: u64,
     bound: u64,
+    /// The recursion depth that the frame was selected at
+    depth: usize,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -63,6 +362,95 @@ struct State {
     stack: Vec<Frame>,
     cursor: usize,
     estimate: f64,
+    depth: usize,
+    /// The maximum size of the current state, if it's being enumerated by size
+    limit: Option<u64>,
+    /// The sum of the values selected so far
+    size: u64,
+    /// The deepest recursion depth that a value was selected at so far
+    deepest: usize,
+    /// The index of the frame that took the state past its limit, along with the size it reached
+    overflow: Option<(usize, u64)>,
+    tier: Option<Tier>,
+}
+
+/// Enumerates the states in tiers of increasing size
+///
+/// The size of a state is the sum of its frame values, which covers list lengths, variant
+/// indexes, and the offsets of integers from their minimum value, plus the deepest recursion
+/// depth that one of its frames was selected at. Each state is run once, in the tier of its own
+/// size, and the states that follow it are queued for the larger tiers. A state that recurses past
+/// the size of its tier is abandoned as soon as it does, and queued for the size it reached.
+#[derive(Clone, Debug, Default)]
+struct Tier {
+    /// The size of the states in the current tier
+    size: u64,
+    /// The number of frames at the start of the current state that were fixed when it was queued
+    floor: usize,
+    /// The state being run, which is kept so a checkpoint can run it again
+    current: Option<Pending>,
+    /// The states waiting for a tier, by their size
+    queue: BTreeMap<u64, VecDeque<Pending>>,
+    /// The largest tier that has been fully enumerated
+    complete: Option<u64>,
+    /// Set when resuming from an arbitrary state, which doesn't cover the smaller tiers
+    partial: bool,
+}
+
+/// A state that's waiting to be enumerated by size
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pending {
+    /// The smallest size that the state can have
+    pub size: u64,
+    /// Only the frames from this index on are incremented to find the states that follow it
+    pub floor: usize,
+    /// The values of the frames that are known, which are followed by zeros
+    pub frames: Vec<u64>,
+}
+
+impl Tier {
+    fn new() -> Self {
+        let mut tier = Self::default();
+        tier.push(Pending {
+            size: 0,
+            floor: 0,
+            frames: Vec::new(),
+        });
+        tier
+    }
+
+    fn push(&mut self, pending: Pending) {
+        self.queue
+            .entry(pending.size)
+            .or_default()
+            .push_back(pending);
+    }
+
+    /// Returns the next state to run, moving on to the next tier if the current one is finished
+    fn pop(&mut self) -> ControlFlow<(), Pending> {
+        self.current = None;
+
+        let Some(mut entry) = self.queue.first_entry() else {
+            if !self.partial {
+                self.complete = Some(self.size);
+            }
+            return ControlFlow::Break(());
+        };
+
+        let size = *entry.key();
+        let pending = entry.get_mut().pop_front().unwrap();
+        if entry.get().is_empty() {
+            entry.remove();
+        }
+
+        if size > self.size && !self.partial {
+            self.complete = Some(size - 1);
+        }
+        self.size = size;
+        self.current = Some(pending.clone());
+
+        ControlFlow::Continue(pending)
+    }
 }
 
 impl State {
@@ -72,47 +460,98 @@ impl State {
 
     fn deserialize(&mut self, state: &[u64]) {
         self.stack.clear();
-        self.stack
-            .extend(state.iter().map(|&v| Frame { value: v, bound: v }));
-        self.cursor = 0;
+        self.stack.extend(state.iter().map(|&v| Frame {
+            value: v,
+            bound: v,
+            depth: 0,
+        }));
+        self.limit = None;
+        self.restart();
         self.started = !state.is_empty();
         self.estimate = 1.0;
     }
 
+    /// Starts running a state that was queued by size
+    fn start(&mut self, pending: Pending) {
+        let tier = self.tier.as_mut().unwrap();
+        tier.floor = pending.floor;
+        self.limit = if tier.partial { None } else { Some(tier.size) };
+
+        self.stack.clear();
+        self.stack.extend(pending.frames.iter().map(|&value| Frame {
+            value,
+            bound: value,
+            depth: 0,
+        }));
+        self.restart();
+    }
+
+    /// Selects the frames of the current state from the start
+    fn restart(&mut self) {
+        self.cursor = 0;
+        self.depth = 0;
+        self.size = 0;
+        self.deepest = 0;
+        self.overflow = None;
+    }
+
     fn estimate(&self) -> f64 {
         self.estimate
     }
 
-    fn step(&mut self) -> ControlFlow<()> {
-        if !self.started {
-            self.started = true;
-            self.estimate = 1.0;
-            return ControlFlow::Continue(());
-        }
+    /// Moves to the next state that differs in the first `len` frames
+    ///
+    /// Returns the index of the frame that was incremented.
+    fn advance(&mut self, len: usize) -> ControlFlow<(), usize> {
+        let len = self.stack.len().min(len);
 
         // depth-first search the state space
-        for i in (0..self.stack.len()).rev() {
-            if self.stack[i].value < self.stack[i].bound {
-                self.stack[i].value += 1;
-                self.stack.truncate(i + 1);
-                self.cursor = 0;
-                return ControlFlow::Continue(());
+        for i in (0..len).rev() {
+            let frame = &mut self.stack[i];
+
+            if frame.value >= frame.bound {
+                continue;
             }
+
+            frame.value += 1;
+            self.stack.truncate(i + 1);
+            self.cursor = 0;
+            return ControlFlow::Continue(i);
         }
 
         ControlFlow::Break(())
     }
 
+    /// Returns the number of frames needed to split the current state into enough prefixes for
+    /// `count` workers
+    fn prefix_depth(&self, count: usize) -> usize {
+        let target = (count as u64).saturating_mul(PREFIXES_PER_SHARD);
+        let mut prefixes = 1u64;
+        for (idx, frame) in self.stack.iter().enumerate() {
+            if prefixes >= target {
+                return idx;
+            }
+            prefixes = prefixes.saturating_mul(frame.bound.saturating_add(1));
+        }
+        self.stack.len()
+    }
+
     /// Returns a value between 0 and `bound` inclusive.
+    ///
+    /// Returns `None` once the state has grown past its size limit.
     #[inline]
-    fn select(&mut self, bound: u64) -> u64 {
+    fn select(&mut self, bound: u64) -> Option<u64> {
+        if self.overflow.is_some() {
+            return None;
+        }
+
         // no point in tracking this since there's only one option
         if bound == 0 {
-            return 0;
+            return Some(0);
         }
 
         while self.cursor >= self.stack.len() {
-            if self.cursor == self.stack.len() {
+            if self.cursor == self.stack.len() && self.tier.is_none() {
                 self.estimate += bound as f64;
             }
 
@@ -124,31 +563,44 @@ impl State {
         self.cursor += 1;
 
         frame.bound = frame.bound.max(bound);
-        frame.value.min(bound)
+        frame.depth = self.depth;
+        let value = frame.value.min(bound);
+
+        if let Some(limit) = self.limit {
+            self.size = self.size.saturating_add(value);
+            self.deepest = self.deepest.max(self.depth);
+            let size = self.size.saturating_add(self.deepest as u64);
+            if size > limit {
+                self.overflow = Some((self.cursor - 1, size));
+                return None;
+            }
+        }
+
+        Some(value)
     }
 
     #[inline]
-    fn select_u128(&mut self, bound: u128) -> u128 {
+    fn select_u128(&mut self, bound: u128) -> Option<u128> {
         // no point in tracking this since it doesn't vary
         if bound == 0 {
-            return 0;
+            return Some(0);
         }
 
         // check if we can generate a value with a single u64
         if bound <= u64::MAX as u128 {
-            return self.select(bound as u64) as u128;
+            return self.select(bound as u64).map(|value| value as u128);
         }
 
-        let mut value = self.select(u64::MAX) as u128;
+        let mut value = self.select(u64::MAX)? as u128;
         let bound = bound - u64::MAX as u128;
 
         // generate the remaining upper bits
-        let upper = self.select(bound as u64);
+        let upper = self.select(bound as u64)?;
 
         // shift the upper bits into place
         value |= (upper as u128) << 64;
 
-        value
+        Some(value)
     }
 }
 
@@ -190,12 +642,12 @@ macro_rules! impl_driver {
         impl super::Driver for $ty {
             #[inline(always)]
             fn depth(&self) -> usize {
-                self.depth
+                self.state.depth
             }
 
             #[inline(always)]
             fn set_depth(&mut self, depth: usize) {
-                self.depth = depth;
+                self.state.depth = depth;
             }
 
             #[inline(always)]
@@ -205,7 +657,7 @@ macro_rules! impl_driver {
 
             #[inline(always)]
             fn gen_variant(&mut self, variants: usize, base_case: usize) -> Option<usize> {
-                if self.depth == self.max_depth {
+                if self.state.depth == self.max_depth {
                     return Some(base_case);
                 }
 
@@ -224,14 +676,14 @@ macro_rules! impl_driver {
             #[inline(always)]
             fn gen_u64(&mut self, min: Bound<&u64>, max: Bound<&u64>) -> Option<u64> {
                 let range = Uniform::bounds_to_range(min, max)?;
-                let value = self.state.select(*range.end() - *range.start());
+                let value = self.state.select(*range.end() - *range.start())?;
                 Some(*range.start() + value)
             }
 
             #[inline(always)]
             fn gen_i64(&mut self, min: Bound<&i64>, max: Bound<&i64>) -> Option<i64> {
                 let range = Uniform::bounds_to_range(min, max)?;
-                let value = self.state.select((*range.end() - *range.start()) as u64);
+                let value = self.state.select((*range.end() - *range.start()) as u64)?;
                 Some(*range.start() + value as i64)
             }
 
@@ -240,7 +692,7 @@ macro_rules! impl_driver {
                 let range = Uniform::bounds_to_range(min, max)?;
 
                 let bound = *range.end() - *range.start();
-                let mut value = self.state.select_u128(bound);
+                let mut value = self.state.select_u128(bound)?;
                 value += *range.start();
                 Some(value)
             }
@@ -250,7 +702,7 @@ macro_rules! impl_driver {
                 let range = Uniform::bounds_to_range(min, max)?;
 
                 let bound = *range.end() - *range.start();
-                let mut value = self.state.select_u128(bound as _) as i128;
+                let mut value = self.state.select_u128(bound as _)? as i128;
                 value += *range.start();
                 Some(value)
             }
@@ -286,7 +738,7 @@ macro_rules! impl_driver {
 
             #[inline(always)]
             fn gen_bool(&mut self, _probability: Option<f32>) -> Option<bool> {
-                Some(self.state.select(1) == 1)
+                Some(self.state.select(1)? == 1)
             }
 
             #[inline(always)]
@@ -305,6 +757,9 @@ macro_rules! impl_driver {
 
                 let len = self.gen_usize(Bound::Included(&min), Bound::Included(&max))?;
                 self.buffer.fill(len, &mut Rng(&mut self.state))?;
+                if self.state.overflow.is_some() {
+                    return None;
+                }
                 let (_consumed, value) = produce(self.buffer.slice_mut(len))?;
                 self.buffer.clear();
                 Some(value)
@@ -320,11 +775,11 @@ struct Rng<'a>(&'a mut State);
 
 impl rand_core::RngCore for Rng<'_> {
     fn next_u32(&mut self) -> u32 {
-        self.0.select(u32::MAX as _) as _
+        self.0.select(u32::MAX as _).unwrap_or_default() as _
     }
 
     fn next_u64(&mut self) -> u64 {
-        self.0.select(u64::MAX)
+        self.0.select(u64::MAX).unwrap_or_default()
     }
 
     fn fill_bytes(&mut self, mut dest: &mut [u8]) {
@@ -335,7 +790,7 @@ impl rand_core::RngCore for Rng<'_> {
             chunk.copy_from_slice(&value.to_be_bytes());
         }
 
-        let value = self.0.select((1 << dest.len()) * 8);
+        let value = self.0.select((1 << dest.len()) * 8).unwrap_or_default();
         dest.copy_from_slice(&value.to_be_bytes()[..dest.len()]);
     }
 }
@@ -414,4 +869,242 @@ mod tests {
 
         assert_eq!(count, range.end().to_bits() - range.start().to_bits() + 1);
     }
+
+    #[test]
+    fn exhaustive_resume_test() {
+        let generate = |driver: &mut Driver| crate::produce::<(u8, bool)>().generate(driver);
+
+        let mut driver = Driver::default();
+        let mut values = vec![];
+        let mut states = vec![];
+        while driver.step().is_continue() {
+            values.push(generate(&mut driver).unwrap());
+            states.push(driver.serialize());
+        }
+
+        // resuming from any state produces the same values as the full enumeration
+        for idx in [0, 1, 100, values.len() - 1] {
+            let mut driver = Driver::default();
+            driver.resume(&states[idx]);
+
+            let mut resumed = vec![];
+            while driver.step().is_continue() {
+                resumed.push(generate(&mut driver).unwrap());
+            }
+
+            assert_eq!(resumed, values[idx..]);
+        }
+    }
+
+    #[test]
+    fn exhaustive_shard_resume_test() {
+        let generator = (crate::produce::<u8>(), 0..10u8);
+        let generate = |driver: &mut Driver| generator.generate(driver);
+
+        let mut driver = Driver::default();
+        driver.set_shard(1, 3);
+        let mut values = vec![];
+        let mut positions = vec![];
+        while driver.step().is_continue() {
+            values.push(generate(&mut driver).unwrap());
+            positions.push((driver.serialize(), driver.shard_position()));
+        }
+
+        for idx in [0, 1, 100, values.len() - 1] {
+            let (state, position) = &positions[idx];
+
+            let mut driver = Driver::default();
+            driver.set_shard(1, 3);
+            driver.resume(state);
+            // the position isn't known until after the first state
+            if let Some((depth, prefix)) = *position {
+                driver.set_shard_position(depth, prefix);
+            }
+
+            let mut resumed = vec![];
+            while driver.step().is_continue() {
+                resumed.push(generate(&mut driver).unwrap());
+            }
+
+            assert_eq!(resumed, values[idx..]);
+        }
+    }
+
+    /// Runs a size-tiered enumeration, returning each tested value along with its tier
+    fn enumerate_tiers<G: crate::ValueGenerator>(
+        generator: &G,
+        options: &crate::driver::Options,
+        shard: Option<(usize, usize)>,
+    ) -> (Vec<(u64, G::Output)>, Driver) {
+        let mut driver = Driver::new(&options.clone().with_size_tiers(true));
+        if let Some((index, count)) = shard {
+            driver.set_shard(index, count);
+        }
+
+        let mut values = vec![];
+        while driver.step().is_continue() {
+            let value = generator.generate(&mut driver);
+            if driver.is_deferred() {
+                assert!(value.is_none());
+                continue;
+            }
+            if let Some(value) = value.filter(|_| driver.is_owned()) {
+                values.push((driver.tier().unwrap(), value));
+            }
+        }
+
+        (values, driver)
+    }
+
+    #[test]
+    fn exhaustive_tier_test() {
+        let generator = crate::produce::<Vec<bool>>().with().len(0usize..=3);
+        // the elements of a list are selected one level deeper
+        let size =
+            |value: &Vec<bool>| (1 + value.len() + value.iter().filter(|v| **v).count()) as u64;
+
+        let (tiers, driver) = enumerate_tiers(&generator, &Default::default(), None);
+
+        // each value is tested once, in the tier of its own size
+        let mut sizes = vec![];
+        for (tier, value) in &tiers {
+            assert_eq!(*tier, size(value), "{value:?}");
+            sizes.push(*tier);
+        }
+        assert!(sizes.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        // the largest value is `[true; 3]`
+        assert_eq!(driver.complete_tier(), Some(7));
+
+        let mut driver = Driver::default();
+        let mut all = vec![];
+        while driver.step().is_continue() {
+            all.push(generator.generate(&mut driver).unwrap());
+        }
+
+        let mut values: Vec<_> = tiers.into_iter().map(|(_, value)| value).collect();
+        values.sort();
+        all.sort();
+        assert_eq!(values, all);
+
+        // each worker of a shard tests its own part of each tier
+        for count in [2, 3, 7] {
+            let mut values = vec![];
+            for index in 0..count {
+                let (tiers, _) =
+                    enumerate_tiers(&generator, &Default::default(), Some((index, count)));
+                values.extend(tiers.into_iter().map(|(_, value)| value));
+            }
+            values.sort();
+            assert_eq!(values, all, "{count}");
+        }
+    }
+
+    #[test]
+    fn exhaustive_tier_depth_test() {
+        /// Counts the number of times `true` was selected, each one level deeper than the last
+        struct Peano;
+
+        impl crate::ValueGenerator for Peano {
+            type Output = usize;
+
+            fn generate<D: crate::Driver>(&self, driver: &mut D) -> Option<usize> {
+                if !driver.gen_bool(None)? {
+                    return Some(0);
+                }
+                driver.depth_guard(|driver| Some(self.generate(driver)? + 1))
+            }
+        }
+
+        let options = crate::driver::Options::default().with_max_depth(4);
+        let (tiers, driver) = enumerate_tiers(&Peano, &options, None);
+
+        // the value and the depth both add to the size
+        assert_eq!(tiers, [(0, 0), (2, 1), (4, 2), (6, 3), (8, 4)]);
+        // the fifth `true` is rejected at the maximum depth, in the last tier
+        assert_eq!(driver.complete_tier(), Some(9));
+    }
+
+    #[test]
+    fn exhaustive_tier_resume_test() {
+        let generator = (
+            0..10u8,
+            crate::produce::<Vec<bool>>().with().len(0usize..=2),
+        );
+        let (all, _) = enumerate_tiers(&generator, &Default::default(), None);
+
+        // resuming from the pending states of any step produces the same values
+        let mut driver = Driver::new(&crate::driver::Options::default().with_size_tiers(true));
+        let mut idx = 0;
+        while driver.step().is_continue() {
+            if idx % 97 == 0 {
+                let mut resumed =
+                    Driver::new(&crate::driver::Options::default().with_size_tiers(true));
+                resumed.resume_tier(driver.tier().unwrap(), &driver.pending());
+                let mut values = vec![];
+                while resumed.step().is_continue() {
+                    if let Some(value) = generator.generate(&mut resumed) {
+                        values.push((resumed.tier().unwrap(), value));
+                    }
+                }
+                assert_eq!(values, all[all.len() - values.len()..]);
+                assert_eq!(resumed.complete_tier(), Some(all.last().unwrap().0));
+            }
+
+            if generator.generate(&mut driver).is_some() {
+                idx += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn exhaustive_shard_test() {
+        let generator = (crate::produce::<bool>(), crate::produce::<u8>(), 0..20u8);
+        let generate = |driver: &mut Driver| generator.generate(driver);
+
+        let mut driver = Driver::default();
+        let mut expected = vec![];
+        while driver.step().is_continue() {
+            expected.push(generate(&mut driver).unwrap());
+        }
+
+        for count in [1, 2, 3, 7, 100] {
+            let mut values = vec![];
+            let mut owned = vec![];
+            let mut runs = vec![];
+            for index in 0..count {
+                let mut driver = Driver::default();
+                driver.set_shard(index, count);
+                let mut run = 0;
+                while driver.step().is_continue() {
+                    let value = generate(&mut driver).unwrap();
+                    if driver.is_owned() {
+                        owned.push(value);
+                    }
+                    values.push(value);
+                    run += 1;
+                }
+                runs.push(run);
+            }
+
+            // each state is owned by exactly one worker
+            owned.sort_unstable();
+            assert_eq!(owned, expected, "{count}");
+
+            // the first state of each prefix is run by every worker
+            values.sort_unstable();
+            values.dedup();
+            assert_eq!(values, expected, "{count}");
+
+            // the work is evenly split while there are enough prefixes to go around
+            if count <= 7 {
+                let max = *runs.iter().max().unwrap() as f64;
+                let min = *runs.iter().min().unwrap() as f64;
+                assert!(max / min < 1.2, "{count} {runs:?}");
+                // each worker also runs the first state of the 512 `(bool, u8)` prefixes
+                let share = (expected.len() / count + 512) as f64;
+                assert!(max <= share, "{count} {runs:?}");
+            }
+        }
+    }
 }
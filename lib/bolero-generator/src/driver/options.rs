@@ -3,21 +3,29 @@ use core::time::Duration;
 #[derive(Clone, Debug, Default)]
 pub struct Options {
     shrink_time: Option<Duration>,
+    shrink_jobs: Option<usize>,
     max_depth: Option<usize>,
     max_len: Option<usize>,
     exhaustive: bool,
+    size_tiers: bool,
 }
 
 impl Options {
     pub const DEFAULT_MAX_DEPTH: usize = 5;
     pub const DEFAULT_MAX_LEN: usize = 4096;
     pub const DEFAULT_SHRINK_TIME: Duration = Duration::from_secs(1);
+    pub const DEFAULT_SHRINK_JOBS: usize = 1;
 
     pub fn with_shrink_time(mut self, shrink_time: Duration) -> Self {
         self.shrink_time = Some(shrink_time);
         self
     }
 
+    pub fn with_shrink_jobs(mut self, shrink_jobs: usize) -> Self {
+        self.shrink_jobs = Some(shrink_jobs);
+        self
+    }
+
     pub fn with_max_depth(mut self, max_depth: usize) -> Self {
         self.max_depth = Some(max_depth);
         self
@@ -33,16 +41,31 @@ impl Options {
         self
     }
 
+    pub fn with_size_tiers(mut self, size_tiers: bool) -> Self {
+        self.size_tiers = size_tiers;
+        self
+    }
+
     pub fn set_exhaustive(&mut self, exhaustive: bool) -> &mut Self {
         self.exhaustive = exhaustive;
         self
     }
 
+    pub fn set_size_tiers(&mut self, size_tiers: bool) -> &mut Self {
+        self.size_tiers = size_tiers;
+        self
+    }
+
     pub fn set_shrink_time(&mut self, shrink_time: Duration) -> &mut Self {
         self.shrink_time = Some(shrink_time);
         self
     }
 
+    pub fn set_shrink_jobs(&mut self, shrink_jobs: usize) -> &mut Self {
+        self.shrink_jobs = Some(shrink_jobs);
+        self
+    }
+
     pub fn set_max_depth(&mut self, max_depth: usize) -> &mut Self {
         self.max_depth = Some(max_depth);
         self
@@ -58,6 +81,12 @@ impl Options {
         self.exhaustive
     }
 
+    /// Returns `true` if the exhaustive driver enumerates the inputs in tiers of increasing size
+    #[inline]
+    pub fn size_tiers(&self) -> bool {
+        self.size_tiers
+    }
+
     #[inline]
     pub fn max_depth(&self) -> Option<usize> {
         self.max_depth
@@ -73,6 +102,12 @@ impl Options {
         self.shrink_time
     }
 
+    /// Returns the number of shrinking candidates that are evaluated at the same time
+    #[inline]
+    pub fn shrink_jobs(&self) -> Option<usize> {
+        self.shrink_jobs
+    }
+
     #[inline]
     pub fn max_depth_or_default(&self) -> usize {
         self.max_depth.unwrap_or(Self::DEFAULT_MAX_DEPTH)
@@ -88,6 +123,11 @@ impl Options {
         self.shrink_time.unwrap_or(Self::DEFAULT_SHRINK_TIME)
     }
 
+    #[inline]
+    pub fn shrink_jobs_or_default(&self) -> usize {
+        self.shrink_jobs.unwrap_or(Self::DEFAULT_SHRINK_JOBS).max(1)
+    }
+
     #[inline]
     pub fn merge_from(&mut self, other: &Self) {
         macro_rules! merge {
@@ -101,5 +141,6 @@ impl Options {
         merge!(max_depth);
         merge!(max_len);
         merge!(shrink_time);
+        merge!(shrink_jobs);
     }
 }
@@ -48,6 +48,7 @@ pub mod num;
 pub mod one_of;
 pub mod prelude;
 pub mod range;
+pub mod rejection;
 pub mod result;
 pub mod time;
 #[cfg(feature = "std")]
@@ -141,14 +142,17 @@ pub trait ValueGenerator: Sized {
     }
 
     /// Filter the value of a generator
+    #[track_caller]
     fn filter_gen<F: Fn(&Self::Output) -> bool>(self, filter: F) -> FilterGenerator<Self, F> {
         FilterGenerator {
             generator: self,
             filter,
+            location: core::panic::Location::caller(),
         }
     }
 
     /// Filter the value of a generator and map it to something else
+    #[track_caller]
     fn filter_map_gen<F: Fn(Self::Output) -> Option<T>, T>(
         self,
         filter_map: F,
@@ -156,6 +160,7 @@ pub trait ValueGenerator: Sized {
         FilterMapGenerator {
             generator: self,
             filter_map,
+            location: core::panic::Location::caller(),
         }
     }
 
@@ -0,0 +1,62 @@
+//! Records why the most recent input was rejected
+//!
+//! Generators signal an invalid input by returning `None`, which doesn't say which combinator
+//! gave up. Filters and [`assume`](crate::any::assume) record a [`Rejection`] here so test
+//! engines can report it alongside the discarded input.
+
+use core::{fmt, panic::Location};
+
+/// The combinator or `assume` call site that rejected an input
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rejection {
+    /// The name of the rejecting combinator
+    pub by: &'static str,
+    /// An optional message describing the rejection
+    pub message: &'static str,
+    /// Where the combinator was created or `assume` was called
+    pub location: &'static Location<'static>,
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rejected by {} at {}", self.by, self.location)?;
+        if !self.message.is_empty() {
+            write!(f, ": {}", self.message)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    static LAST: core::cell::Cell<Option<Rejection>> = const { core::cell::Cell::new(None) };
+}
+
+/// Records `rejection` as the reason the current input is invalid
+#[inline]
+pub(crate) fn record(rejection: Rejection) {
+    #[cfg(feature = "std")]
+    LAST.with(|last| last.set(Some(rejection)));
+    #[cfg(not(feature = "std"))]
+    let _ = rejection;
+}
+
+/// Records `rejection` unless a more specific reason was already recorded
+#[cfg(feature = "any")]
+#[inline]
+pub(crate) fn record_fallback(rejection: Rejection) {
+    LAST.with(|last| {
+        if last.get().is_none() {
+            last.set(Some(rejection));
+        }
+    });
+}
+
+/// Returns and clears the most recently recorded rejection on the current thread
+#[inline]
+pub fn take() -> Option<Rejection> {
+    #[cfg(feature = "std")]
+    return LAST.with(|last| last.take());
+    #[cfg(not(feature = "std"))]
+    return None;
+}
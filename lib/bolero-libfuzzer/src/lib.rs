@@ -66,11 +66,7 @@ pub mod fuzzer {
                             let input = input::Bytes::new(slice, options);
                             eprintln!(
                                 "{:#}",
-                                Failure {
-                                    seed: None,
-                                    error,
-                                    input
-                                }
+                                Failure::new(error, input, None)
                             );
                         }
 
@@ -119,11 +115,7 @@ pub mod fuzzer {
                     Err(error) => {
                         eprintln!(
                             "{:#}",
-                            Failure {
-                                seed: None,
-                                error,
-                                input: (),
-                            }
+                            Failure::new(error, (), None)
                         );
 
                         std::process::abort();
@@ -35,7 +35,10 @@ pub use bolero_generator::prelude::*;
 #[doc(hidden)]
 pub use bolero_engine::{self, TargetLocation, __item_path__};
 
-pub use bolero_engine::{Driver, Engine, Test};
+pub use bolero_engine::{shrink::ShrinkPass, Driver, Engine, Test};
+
+pub mod observe;
+pub use observe::{argument, event, event_with_payload, target};
 
 #[cfg(test)]
 mod tests;
@@ -166,6 +169,29 @@ macro_rules! fuzz {
     }
 }
 
+/// Requires `condition` to hold in at least `min_percent` percent of valid test cases
+///
+/// The default test engine checks each requirement once the run reaches its time or iteration
+/// limit and fails the test if the labelled condition was under-sampled. The macro evaluates to
+/// `condition`.
+///
+/// A requirement is only known once a test case reaches it. Declare requirements that might
+/// never be reached with [`TestTarget::with_cover`] so they still fail the run.
+///
+/// ```rust
+/// use bolero::{check, cover};
+///
+/// check!().with_type().for_each(|value: &u8| {
+///     cover!(*value < 128, 25.0, "lower half");
+/// });
+/// ```
+#[macro_export]
+macro_rules! cover {
+    ($condition:expr, $min_percent:expr, $label:expr $(,)?) => {
+        $crate::observe::cover($condition, $min_percent, $label)
+    };
+}
+
 /// Configuration for a test target
 pub struct TestTarget<Generator, Engine, InputOwnership> {
     generator: Generator,
@@ -182,6 +208,10 @@ pub struct BorrowedInput;
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct ClonedInput;
 
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SharedInput<InputOwnership>(PhantomData<InputOwnership>);
+
 #[doc(hidden)]
 pub fn test(
     location: TargetLocation,
@@ -282,6 +312,29 @@ impl<G, Engine, InputOwnership> TestTarget<G, Engine, InputOwnership> {
         self
     }
 
+    /// Set the number of shrinking candidates that are evaluated at the same time
+    ///
+    /// Each candidate is replayed in a child process of the test binary, or on a worker thread
+    /// with [`TestTarget::with_shrink_threads`], which can speed up shrinking for slow tests. This
+    /// can also be set with `BOLERO_SHRINK_JOBS`.
+    pub fn with_shrink_jobs(mut self, shrink_jobs: usize) -> Self {
+        self.driver_options.set_shrink_jobs(shrink_jobs);
+        self
+    }
+
+    /// Evaluate the shrinking candidates on worker threads instead of child processes
+    ///
+    /// This requires the test function and the generator to be `Clone + Send + Sync`, and only takes
+    /// effect with more than one shrink job. It should be called after [`TestTarget::cloned`].
+    pub fn with_shrink_threads(self) -> TestTarget<G, Engine, SharedInput<InputOwnership>> {
+        TestTarget {
+            generator: self.generator,
+            engine: self.engine,
+            driver_options: self.driver_options,
+            input_ownership: PhantomData,
+        }
+    }
+
     /// Exhaustively iterates over all of the possible inputs
     ///
     /// Note that if the search space is large this can take a long time.
@@ -289,6 +342,19 @@ impl<G, Engine, InputOwnership> TestTarget<G, Engine, InputOwnership> {
         self.driver_options.set_exhaustive(true);
         self
     }
+
+    /// Exhaustively iterates over the possible inputs in tiers of increasing size
+    ///
+    /// The size of an input is the sum of the choices made while generating it, such as list
+    /// lengths, enum variants, and integer values, plus the depth of its deepest choice. Each input
+    /// is tested once, and every input of a size is tested before moving on to the next size, so a
+    /// run that's limited by `with_test_time` still fully covers the smaller inputs. The largest
+    /// fully covered size is included in the test report.
+    pub fn exhaustive_by_size(mut self) -> Self {
+        self.driver_options.set_exhaustive(true);
+        self.driver_options.set_size_tiers(true);
+        self
+    }
 }
 
 impl<G: generator::ValueGenerator, Engine, InputOwnership> TestTarget<G, Engine, InputOwnership> {
@@ -322,6 +388,7 @@ impl<G: generator::ValueGenerator, Engine, InputOwnership> TestTarget<G, Engine,
     }
 
     /// Filter the value of the generator
+    #[track_caller]
     pub fn filter<F: Fn(&G::Output) -> bool>(
         self,
         filter: F,
@@ -335,6 +402,7 @@ impl<G: generator::ValueGenerator, Engine, InputOwnership> TestTarget<G, Engine,
     }
 
     /// Filter the value of the generator and map it to something else
+    #[track_caller]
     pub fn filter_map<F: Fn(G::Output) -> Option<T>, T>(
         self,
         filter_map: F,
@@ -384,6 +452,18 @@ cfg_if::cfg_if! {
                 self.driver_options.set_max_len(max_len);
                 self
             }
+
+            /// Add a pass for shrinking failing inputs
+            pub fn with_shrink_pass<P: ShrinkPass + 'static>(self, pass: P) -> Self {
+                let _ = pass;
+                self
+            }
+
+            /// Declare a [`cover!`] requirement that fails the run if it's never reached
+            pub fn with_cover(self, label: &str, min_percent: f64) -> Self {
+                let _ = (label, min_percent);
+                self
+            }
         }
     } else {
         impl<G, InputOwnership> TestTarget<G, crate::test::TestEngine, InputOwnership> {
@@ -405,6 +485,33 @@ cfg_if::cfg_if! {
                 self.engine.with_max_len(max_len);
                 self
             }
+
+            /// Add a pass for shrinking failing inputs
+            ///
+            /// The pass runs alongside the built-in passes and proposes simpler versions of the
+            /// input bytes, like replacing a packet with a canonical one. The failure report
+            /// lists the passes that shrunk the input.
+            pub fn with_shrink_pass<P: ShrinkPass + 'static>(mut self, pass: P) -> Self {
+                self.engine.with_shrink_pass(Box::new(pass));
+                self
+            }
+
+            /// Declare a [`cover!`] requirement that fails the run if it's never reached
+            ///
+            /// ```rust
+            /// use bolero::{check, cover};
+            ///
+            /// check!()
+            ///     .with_type()
+            ///     .with_cover("empty", 1.0)
+            ///     .for_each(|value: &Vec<u8>| {
+            ///         cover!(value.is_empty(), 1.0, "empty");
+            ///     });
+            /// ```
+            pub fn with_cover(mut self, label: &str, min_percent: f64) -> Self {
+                self.engine.with_cover(label, min_percent);
+                self
+            }
         }
     }
 }
@@ -493,3 +600,59 @@ impl<E> TestTarget<ByteSliceGenerator, E, ClonedInput> {
         self.engine.run(test, self.driver_options)
     }
 }
+
+impl<G, E> TestTarget<G, E, SharedInput<BorrowedInput>>
+where
+    G: generator::ValueGenerator,
+{
+    /// Iterate over all of the inputs and check the `TestTarget`
+    pub fn for_each<F>(self, test: F) -> E::Output
+    where
+        E: Engine<bolero_engine::SharedTest<bolero_engine::BorrowedGeneratorTest<F, G, G::Output>>>,
+        bolero_engine::SharedTest<bolero_engine::BorrowedGeneratorTest<F, G, G::Output>>: Test,
+    {
+        let test = bolero_engine::BorrowedGeneratorTest::new(test, self.generator);
+        let test = bolero_engine::SharedTest::new(test);
+        self.engine.run(test, self.driver_options)
+    }
+}
+
+impl<G, E> TestTarget<G, E, SharedInput<ClonedInput>>
+where
+    G: generator::ValueGenerator,
+{
+    /// Iterate over all of the inputs and check the `TestTarget`
+    pub fn for_each<F>(self, test: F) -> E::Output
+    where
+        E: Engine<bolero_engine::SharedTest<bolero_engine::ClonedGeneratorTest<F, G, G::Output>>>,
+        bolero_engine::SharedTest<bolero_engine::ClonedGeneratorTest<F, G, G::Output>>: Test,
+    {
+        let test = bolero_engine::ClonedGeneratorTest::new(test, self.generator);
+        let test = bolero_engine::SharedTest::new(test);
+        self.engine.run(test, self.driver_options)
+    }
+}
+
+impl<E> TestTarget<ByteSliceGenerator, E, SharedInput<BorrowedInput>> {
+    /// Iterate over all of the inputs and check the `TestTarget`
+    pub fn for_each<T>(self, test: T) -> E::Output
+    where
+        E: Engine<bolero_engine::SharedTest<bolero_engine::BorrowedSliceTest<T>>>,
+        bolero_engine::SharedTest<bolero_engine::BorrowedSliceTest<T>>: Test,
+    {
+        let test = bolero_engine::SharedTest::new(bolero_engine::BorrowedSliceTest::new(test));
+        self.engine.run(test, self.driver_options)
+    }
+}
+
+impl<E> TestTarget<ByteSliceGenerator, E, SharedInput<ClonedInput>> {
+    /// Iterate over all of the inputs and check the `TestTarget`
+    pub fn for_each<T>(self, test: T) -> E::Output
+    where
+        E: Engine<bolero_engine::SharedTest<bolero_engine::ClonedSliceTest<T>>>,
+        bolero_engine::SharedTest<bolero_engine::ClonedSliceTest<T>>: Test,
+    {
+        let test = bolero_engine::SharedTest::new(bolero_engine::ClonedSliceTest::new(test));
+        self.engine.run(test, self.driver_options)
+    }
+}
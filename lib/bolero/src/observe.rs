@@ -0,0 +1,216 @@
+//! Records observations about the currently executing test case
+//!
+//! Engines that support observability (e.g. the default test engine with `BOLERO_TYCHE`
+//! set) collect these values for each test case. Otherwise, recording is a no-op.
+#![cfg_attr(any(fuzzing, kani), allow(dead_code))]
+
+use core::fmt::Debug;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A value attached to a named feature of a test case
+#[derive(Clone, Debug, PartialEq)]
+pub enum Payload {
+    Integer(i128),
+    Float(f64),
+    String(String),
+}
+
+macro_rules! impl_payload {
+    ($variant:ident, $as:ty, [$($ty:ty),*]) => {
+        $(
+            impl From<$ty> for Payload {
+                #[inline]
+                fn from(value: $ty) -> Self {
+                    Self::$variant(value as $as)
+                }
+            }
+        )*
+    };
+}
+
+impl_payload!(
+    Integer,
+    i128,
+    [u8, i8, u16, i16, u32, i32, u64, i64, i128, usize, isize]
+);
+impl_payload!(Float, f64, [f32, f64]);
+
+impl From<&str> for Payload {
+    #[inline]
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for Payload {
+    #[inline]
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+/// Observations recorded while executing a single test case
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Record {
+    pub features: BTreeMap<String, Payload>,
+    /// The labels recorded with [`event`], which are also kept in `features`
+    pub events: BTreeSet<String>,
+    pub arguments: BTreeMap<String, String>,
+    pub targets: BTreeMap<String, f64>,
+    pub covers: BTreeMap<String, Cover>,
+}
+
+/// A coverage requirement recorded with [`cover!`](crate::cover)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Cover {
+    pub hit: bool,
+    pub min_percent: f64,
+}
+
+/// Records that a labelled event occurred in the current test case
+///
+/// Engines report how often each event occurred at the end of the run, which helps
+/// check that interesting cases are actually being generated.
+///
+/// ```rust
+/// bolero::check!().with_type().for_each(|value: &Vec<u8>| {
+///     if value.is_empty() {
+///         bolero::event("empty");
+///     }
+/// });
+/// ```
+pub fn event(label: &str) {
+    with_record(|record| {
+        record
+            .features
+            .insert(label.to_string(), Payload::String(String::new()));
+        record.events.insert(label.to_string());
+    });
+}
+
+/// Records a labelled score for the current test case
+///
+/// Engines report the distribution of each target's scores at the end of the run. If a
+/// label is targeted more than once in a test case, only the last score is kept.
+///
+/// ```rust
+/// bolero::check!().with_type().for_each(|value: &Vec<u8>| {
+///     bolero::target(value.len() as f64, "len");
+/// });
+/// ```
+pub fn target(score: f64, label: &str) {
+    with_record(|record| {
+        record.targets.insert(label.to_string(), score);
+    });
+}
+
+/// Records whether the labelled coverage requirement was hit in the current test case
+///
+/// This is usually called through the [`cover!`](crate::cover) macro. Returns `condition`.
+pub fn cover(condition: bool, min_percent: f64, label: &str) -> bool {
+    with_record(|record| {
+        let cover = record.covers.entry(label.to_string()).or_insert(Cover {
+            hit: false,
+            min_percent,
+        });
+        // a requirement that's checked several times counts as hit if any of them held
+        cover.hit |= condition;
+        cover.min_percent = min_percent;
+    });
+    condition
+}
+
+/// Records a named feature with a payload for the current test case
+///
+/// ```rust
+/// bolero::check!().with_type().for_each(|value: &Vec<u8>| {
+///     bolero::event_with_payload("len", value.len());
+/// });
+/// ```
+pub fn event_with_payload<P: Into<Payload>>(label: &str, payload: P) {
+    with_record(|record| {
+        record.features.insert(label.to_string(), payload.into());
+    });
+}
+
+/// Records the value of a named argument for the current test case
+///
+/// ```rust
+/// bolero::check!().with_type().for_each(|(a, b): &(u8, u8)| {
+///     bolero::argument("a", a);
+///     bolero::argument("b", b);
+/// });
+/// ```
+pub fn argument<T: Debug + ?Sized>(name: &str, value: &T) {
+    with_record(|record| {
+        record
+            .arguments
+            .insert(name.to_string(), format!("{value:?}"));
+    });
+}
+
+#[cfg(not(kani))]
+mod storage {
+    use super::Record;
+    use core::cell::RefCell;
+
+    thread_local! {
+        static RECORD: RefCell<Option<Record>> = const { RefCell::new(None) };
+    }
+
+    #[inline]
+    pub fn with_record<F: FnOnce(&mut Record)>(f: F) {
+        RECORD.with(|record| {
+            if let Some(record) = record.borrow_mut().as_mut() {
+                f(record);
+            }
+        })
+    }
+
+    #[inline]
+    pub fn replace(value: Option<Record>) -> Option<Record> {
+        RECORD.with(|record| core::mem::replace(&mut *record.borrow_mut(), value))
+    }
+}
+
+#[cfg(kani)]
+mod storage {
+    use super::Record;
+
+    #[inline]
+    pub fn with_record<F: FnOnce(&mut Record)>(f: F) {
+        let _ = f;
+    }
+
+    #[inline]
+    pub fn replace(value: Option<Record>) -> Option<Record> {
+        let _ = value;
+        None
+    }
+}
+
+use storage::with_record;
+
+/// Starts recording observations for a new test case
+#[inline]
+pub(crate) fn start() {
+    storage::replace(Some(Record::default()));
+}
+
+/// Stops recording and returns the observations for the current test case
+#[inline]
+pub(crate) fn finish() -> Option<Record> {
+    storage::replace(None)
+}
+
+/// Executes `f` without recording any of its observations
+///
+/// This is used when re-executing a test case, e.g. while shrinking, so the
+/// original observations are not polluted by the extra runs.
+#[inline]
+pub(crate) fn suspend<F: FnOnce() -> R, R>(f: F) -> R {
+    let prev = storage::replace(None);
+    let res = f();
+    storage::replace(prev);
+    res
+}
@@ -0,0 +1,254 @@
+//! Checkpoints for long exhaustive runs
+//!
+//! Exhaustive targets can take longer to enumerate than a CI job is allowed to run. The driver's
+//! state is periodically written to the `exhaustive` directory of the target's work dir, and the
+//! next run resumes from it. Once the enumeration finishes, a marker is written instead, which
+//! skips the target until its test binary or options change.
+//!
+//! Checkpoints are written by default when running with `cargo bolero test`, and can be enabled
+//! or disabled by setting `BOLERO_EXHAUSTIVE_CHECKPOINT` to `1` or `0`.
+
+use super::token::Token;
+use bolero_engine::driver::{self, exhaustive::Pending};
+use core::time::Duration;
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    io,
+    path::{Path, PathBuf},
+};
+
+/// How often the progress of the enumeration is saved
+pub const INTERVAL: Duration = Duration::from_secs(10);
+
+/// The saved progress of an exhaustive target
+pub struct Checkpoint {
+    dir: PathBuf,
+    /// Distinguishes the checkpoints of each `cargo bolero test --jobs` worker
+    name: String,
+    fingerprint: String,
+}
+
+/// The progress of an exhaustive enumeration
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Progress {
+    /// The serialized state of the last input that was tested
+    pub state: Vec<u64>,
+    /// The size tier being enumerated, if the driver uses them
+    pub tier: Option<u64>,
+    /// The states that are waiting to be enumerated by size
+    pub pending: Vec<Pending>,
+    /// The prefix depth and ordinal of a worker's shard
+    pub shard: Option<(usize, usize)>,
+    /// The number of inputs tested across all of the runs
+    pub inputs: u64,
+    /// The time spent testing across all of the runs
+    pub elapsed: Duration,
+}
+
+impl Checkpoint {
+    pub fn new(dir: PathBuf, shard: Option<(usize, usize)>, options: &driver::Options) -> Self {
+        let name = match shard {
+            Some((index, count)) => format!("{index}-of-{count}"),
+            None => "all".to_string(),
+        };
+
+        // the enumeration is only valid for the same test and options
+        let mut hasher = DefaultHasher::new();
+        if let Some(exe) = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.metadata().ok())
+        {
+            exe.len().hash(&mut hasher);
+            exe.modified().ok().hash(&mut hasher);
+        }
+        options.max_depth_or_default().hash(&mut hasher);
+        options.max_len_or_default().hash(&mut hasher);
+        options.size_tiers().hash(&mut hasher);
+
+        Self {
+            dir,
+            name,
+            fingerprint: format!("{:016x}", hasher.finish()),
+        }
+    }
+
+    /// The marker that records a finished enumeration
+    pub fn marker(&self) -> PathBuf {
+        self.dir.join(format!("complete-{}", self.name))
+    }
+
+    fn path(&self) -> PathBuf {
+        self.dir.join(format!("checkpoint-{}", self.name))
+    }
+
+    /// Returns the progress of a finished enumeration of the same target
+    pub fn completed(&self) -> Option<Progress> {
+        self.read(&self.marker())
+    }
+
+    /// Returns the progress of an unfinished enumeration of the same target
+    pub fn load(&self) -> Option<Progress> {
+        self.read(&self.path())
+    }
+
+    pub fn save(&self, progress: &Progress) -> io::Result<()> {
+        self.write(&self.path(), progress)
+    }
+
+    /// Records that the enumeration finished and removes its checkpoint
+    pub fn complete(&self, progress: &Progress) -> io::Result<()> {
+        self.write(&self.marker(), progress)?;
+        match std::fs::remove_file(self.path()) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    fn read(&self, path: &Path) -> Option<Progress> {
+        let contents = std::fs::read_to_string(path).ok()?;
+        let mut progress = Progress::default();
+        let mut fingerprint = None;
+
+        for line in contents.lines() {
+            let (key, value) = line.split_once('=')?;
+            match key {
+                "fingerprint" => fingerprint = Some(value),
+                "state" => progress.state = value.parse::<Token>().ok()?.frames().ok()?,
+                "tier" => progress.tier = Some(value.parse().ok()?),
+                "pending" => {
+                    let mut parts = value.splitn(3, ',');
+                    progress.pending.push(Pending {
+                        size: parts.next()?.parse().ok()?,
+                        floor: parts.next()?.parse().ok()?,
+                        frames: parts.next()?.parse::<Token>().ok()?.frames().ok()?,
+                    });
+                }
+                "shard" => {
+                    let (depth, prefix) = value.split_once(',')?;
+                    progress.shard = Some((depth.parse().ok()?, prefix.parse().ok()?));
+                }
+                "inputs" => progress.inputs = value.parse().ok()?,
+                "elapsed_ms" => progress.elapsed = Duration::from_millis(value.parse().ok()?),
+                _ => {}
+            }
+        }
+
+        (fingerprint == Some(self.fingerprint.as_str())).then_some(progress)
+    }
+
+    fn write(&self, path: &Path, progress: &Progress) -> io::Result<()> {
+        let mut contents = format!("fingerprint={}\n", self.fingerprint);
+        contents.push_str(&format!("state={}\n", Token::exhaustive(&progress.state)));
+        if let Some(tier) = progress.tier {
+            contents.push_str(&format!("tier={tier}\n"));
+        }
+        for pending in &progress.pending {
+            contents.push_str(&format!(
+                "pending={},{},{}\n",
+                pending.size,
+                pending.floor,
+                Token::exhaustive(&pending.frames)
+            ));
+        }
+        if let Some((depth, prefix)) = progress.shard {
+            contents.push_str(&format!("shard={depth},{prefix}\n"));
+        }
+        contents.push_str(&format!("inputs={}\n", progress.inputs));
+        contents.push_str(&format!("elapsed_ms={}\n", progress.elapsed.as_millis()));
+
+        // write to a temporary file first so a killed run doesn't leave a partial checkpoint
+        std::fs::create_dir_all(&self.dir)?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, contents)?;
+        std::fs::rename(&tmp, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_test() {
+        let dir = std::env::temp_dir().join(format!("bolero-{}-checkpoint", std::process::id()));
+        let options = driver::Options::default();
+        let checkpoint = Checkpoint::new(dir.clone(), None, &options);
+
+        let progress = Progress {
+            state: vec![1, 255, 0],
+            tier: Some(3),
+            pending: vec![
+                Pending {
+                    size: 3,
+                    floor: 0,
+                    frames: vec![],
+                },
+                Pending {
+                    size: 4,
+                    floor: 1,
+                    frames: vec![1, 3],
+                },
+            ],
+            shard: Some((2, 17)),
+            inputs: 1234,
+            elapsed: Duration::from_millis(5678),
+        };
+
+        assert_eq!(checkpoint.load(), None);
+        checkpoint.save(&progress).unwrap();
+        assert_eq!(checkpoint.load(), Some(progress.clone()));
+
+        // the checkpoints of other workers and options are ignored
+        assert_eq!(
+            Checkpoint::new(dir.clone(), Some((0, 2)), &options).load(),
+            None
+        );
+        let options = options.with_max_depth(2);
+        assert_eq!(Checkpoint::new(dir.clone(), None, &options).load(), None);
+
+        assert_eq!(checkpoint.completed(), None);
+        checkpoint.complete(&progress).unwrap();
+        assert_eq!(checkpoint.load(), None);
+        assert_eq!(checkpoint.completed(), Some(progress));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn engine_test() {
+        let dir = std::env::temp_dir().join(format!("bolero-{}-checkpoints", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let run = |seen: &std::sync::Mutex<Vec<u8>>| {
+            let mut target = crate::check!();
+            target.engine.with_checkpoints_dir(dir.clone());
+            target
+                .with_type::<u8>()
+                .exhaustive()
+                .for_each(|value| seen.lock().unwrap().push(*value));
+        };
+        // pretend a previous run was stopped after testing 250
+        let options = driver::Options::default().with_exhaustive(true);
+        let checkpoint = Checkpoint::new(dir.clone(), None, &options);
+        let progress = Progress {
+            state: vec![250],
+            inputs: 251,
+            ..Default::default()
+        };
+        checkpoint.save(&progress).unwrap();
+
+        let seen = std::sync::Mutex::new(vec![]);
+        run(&seen);
+        assert_eq!(seen.into_inner().unwrap(), [250, 251, 252, 253, 254, 255]);
+
+        // the finished enumeration is skipped
+        let completed = checkpoint.completed().unwrap();
+        assert_eq!(completed.inputs, 256);
+        let seen = std::sync::Mutex::new(vec![]);
+        run(&seen);
+        assert!(seen.into_inner().unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
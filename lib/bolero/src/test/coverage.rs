@@ -0,0 +1,46 @@
+//! Line coverage for Tyche observations
+//!
+//! When the test binary is built with `-Cinstrument-coverage --cfg bolero_coverage`, the LLVM
+//! profile counters are compared before and after each test case and mapped back to the
+//! executed source lines. `cargo bolero test` enables this automatically when `BOLERO_TYCHE` is
+//! set. Otherwise, test cases don't report any coverage.
+
+use std::collections::BTreeMap;
+
+/// Executed line numbers, keyed by source file
+pub type Lines = BTreeMap<String, Vec<u32>>;
+
+#[cfg(bolero_coverage)]
+mod llvm;
+#[cfg(any(bolero_coverage, test))]
+mod mapping;
+
+#[cfg(bolero_coverage)]
+pub use llvm::{suspend, Tracker};
+
+#[cfg(not(bolero_coverage))]
+pub struct Tracker(());
+
+#[cfg(not(bolero_coverage))]
+impl Tracker {
+    /// Starts tracking coverage for a single test case
+    ///
+    /// Returns `None` if the binary isn't instrumented.
+    #[inline]
+    pub fn start() -> Option<Self> {
+        None
+    }
+
+    /// Returns the lines executed since the tracker was started
+    #[inline]
+    pub fn finish(self) -> Lines {
+        Lines::new()
+    }
+}
+
+/// Executes `f` without attributing any of its coverage to the current test case
+#[cfg(not(bolero_coverage))]
+#[inline]
+pub fn suspend<F: FnOnce() -> R, R>(f: F) -> R {
+    f()
+}
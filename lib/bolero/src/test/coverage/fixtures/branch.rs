@@ -0,0 +1,8 @@
+fn main() {
+    let count = std::env::args().count();
+    if count > 1 {
+        println!("many");
+    } else {
+        println!("one");
+    }
+}
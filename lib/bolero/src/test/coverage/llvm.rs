@@ -0,0 +1,221 @@
+//! Maps the LLVM profile counters of the running binary to source lines
+//!
+//! The counters live in memory and are updated by the instrumented code. The mapping from
+//! counters to source regions is stored in the `__llvm_covmap` and `__llvm_covfun` sections
+//! of the executable, which aren't loaded at runtime, so they're read from disk once and
+//! cached for the rest of the process.
+//!
+//! The counters are never reset. Instead, each test case takes a snapshot of them when it starts
+//! and compares it with another one when it finishes. Since the counters are shared by the whole
+//! process, test cases running on other threads at the same time are included in each other's
+//! coverage.
+
+use super::{
+    mapping::{invalid, unsupported, Mapping},
+    Lines,
+};
+use core::{
+    cell::{Cell, RefCell},
+    ops::Range,
+    sync::atomic::{AtomicU64, Ordering},
+};
+use object::{Object, ObjectSection};
+use std::{
+    collections::HashMap,
+    io,
+    sync::{Mutex, PoisonError},
+};
+
+extern "C" {
+    fn __llvm_profile_begin_counters() -> *mut u64;
+    fn __llvm_profile_end_counters() -> *mut u64;
+    fn __llvm_profile_begin_data() -> *const u8;
+    fn __llvm_profile_end_data() -> *const u8;
+    static __llvm_profile_raw_version: u64;
+}
+
+/// The mapping is loaded by the first test case and kept for the rest of the process
+static MAPPING: Mutex<Option<Option<&'static Mapping>>> = Mutex::new(None);
+
+thread_local! {
+    /// The number of trackers running on the current thread
+    static TRACKERS: Cell<usize> = const { Cell::new(0) };
+    static IS_SUSPENDED: Cell<bool> = const { Cell::new(false) };
+    /// The counts of all of the code that was suspended on the current thread
+    static SUSPENDED: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+}
+
+pub struct Tracker {
+    mapping: &'static Mapping,
+    start: Vec<u64>,
+    suspended: Vec<u64>,
+}
+
+impl Tracker {
+    /// Starts tracking coverage for a single test case
+    ///
+    /// Returns `None` if the coverage mapping couldn't be loaded.
+    pub fn start() -> Option<Self> {
+        let mapping = {
+            let mut mapping = MAPPING.lock().unwrap_or_else(PoisonError::into_inner);
+            (*mapping.get_or_insert_with(load))?
+        };
+
+        TRACKERS.with(|v| v.set(v.get() + 1));
+
+        Some(Self {
+            mapping,
+            start: snapshot(),
+            suspended: SUSPENDED.with(|v| v.borrow().clone()),
+        })
+    }
+
+    /// Returns the lines executed since the tracker was started
+    pub fn finish(self) -> Lines {
+        let mut counts = snapshot();
+        let suspended = SUSPENDED.with(|v| v.borrow().clone());
+
+        for (idx, count) in counts.iter_mut().enumerate() {
+            let at = |counts: &[u64]| counts.get(idx).copied().unwrap_or(0);
+            let suspended = at(&suspended) - at(&self.suspended);
+            *count = count
+                .saturating_sub(self.start[idx])
+                .saturating_sub(suspended);
+        }
+
+        self.mapping.lines(&counts)
+    }
+}
+
+impl Drop for Tracker {
+    fn drop(&mut self) {
+        TRACKERS.with(|v| v.set(v.get() - 1));
+    }
+}
+
+/// Executes `f` without attributing any of its coverage to the current test case
+pub fn suspend<F: FnOnce() -> R, R>(f: F) -> R {
+    if TRACKERS.with(Cell::get) == 0 || IS_SUSPENDED.with(|v| v.replace(true)) {
+        return f();
+    }
+
+    let prev = snapshot();
+    let res = f();
+    let counts = snapshot();
+    IS_SUSPENDED.with(|v| v.set(false));
+
+    SUSPENDED.with(|suspended| {
+        let mut suspended = suspended.borrow_mut();
+        suspended.resize(counts.len(), 0);
+        for ((suspended, count), prev) in suspended.iter_mut().zip(counts).zip(prev) {
+            *suspended += count.saturating_sub(prev);
+        }
+    });
+
+    res
+}
+
+/// Loads the coverage mapping of the current executable
+fn load() -> Option<&'static Mapping> {
+    match load_mapping() {
+        Ok(mapping) => Some(Box::leak(Box::new(mapping))),
+        // the test still runs, and anything that uses the lines falls back to the generated values
+        Err(err) if err.kind() == io::ErrorKind::Unsupported => {
+            eprintln!(
+                "coverage isn't reported since this toolchain's profile can't be read: {err}"
+            );
+            None
+        }
+        Err(err) => {
+            eprintln!("could not load coverage mapping: {err}");
+            None
+        }
+    }
+}
+
+fn load_mapping() -> io::Result<Mapping> {
+    let exe = std::fs::read(std::env::current_exe()?)?;
+    let exe = object::File::parse(&*exe).map_err(invalid)?;
+
+    let section = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| exe.section_by_name(name))
+            .ok_or_else(|| invalid(format!("missing {} section", names[0])))?
+            .data()
+            .map_err(invalid)
+    };
+
+    let covmap = section(&["__llvm_covmap", ".lcovmap$M"])?;
+    let covfun = section(&["__llvm_covfun", ".lcovfun$M"])?;
+
+    Mapping::parse(covmap, covfun, &profile_counters()?)
+}
+
+/// Returns the location and number of the profile counters
+fn counters() -> (*const u64, usize) {
+    unsafe {
+        let begin = __llvm_profile_begin_counters();
+        let end = __llvm_profile_end_counters();
+        (begin, end.offset_from(begin) as usize)
+    }
+}
+
+/// Copies the current value of each counter
+///
+/// The instrumented code keeps updating the counters, possibly on other threads, so they're
+/// only ever read through atomic loads rather than borrowed as a slice.
+fn snapshot() -> Vec<u64> {
+    let (begin, len) = counters();
+    (0..len)
+        .map(|idx| unsafe { (*begin.add(idx).cast::<AtomicU64>()).load(Ordering::Relaxed) })
+        .collect()
+}
+
+/// Reads the counter range of each function from the profile data records in memory
+fn profile_counters() -> io::Result<HashMap<(u64, u64), Range<usize>>> {
+    let version = unsafe { __llvm_profile_raw_version };
+
+    if version & (1 << 60) != 0 {
+        return Err(unsupported("single byte coverage isn't supported"));
+    }
+
+    // the offset of the `NumCounters` field and the size of each record
+    let (num_counters_offset, record_len) = match version as u32 {
+        8 => (40, 48),
+        9 | 10 => (48, 64),
+        version => {
+            return Err(unsupported(format!(
+                "raw profile version {version} isn't supported, only versions 8 to 10 are"
+            )))
+        }
+    };
+
+    let mut functions = HashMap::new();
+
+    unsafe {
+        let (base, num_counters) = counters();
+        let base = base as isize;
+        let mut data = __llvm_profile_begin_data();
+        let end = __llvm_profile_end_data();
+
+        while data < end {
+            let field = |offset: usize| data.add(offset).cast::<u64>().read_unaligned();
+            let name = field(0);
+            let hash = field(8);
+            // the counter pointer is relative to the record
+            let start = data as isize + field(16) as isize;
+            let len = data.add(num_counters_offset).cast::<u32>().read_unaligned() as usize;
+
+            let start = (start - base) as usize / core::mem::size_of::<u64>();
+            if start + len > num_counters {
+                return Err(invalid("counters out of bounds"));
+            }
+            functions.insert((name, hash), start..start + len);
+
+            data = data.add(record_len);
+        }
+    }
+
+    Ok(functions)
+}
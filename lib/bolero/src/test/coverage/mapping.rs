@@ -0,0 +1,478 @@
+//! Decodes the LLVM coverage mapping into the source lines covered by each counter
+//!
+//! The mapping is made up of the `__llvm_covmap` section, which holds the filenames of each
+//! translation unit, and the `__llvm_covfun` section, which holds the code regions of each
+//! function and the counter expressions that determine if they were executed.
+//!
+//! See <https://llvm.org/docs/CoverageMappingFormat.html> for the format.
+
+use super::Lines;
+use core::ops::Range;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    io,
+};
+
+/// The zero-based coverage mapping versions that can be decoded
+///
+/// Versions 6 and 7 store the compilation directory as the first filename of each translation
+/// unit, which relative filenames are joined with.
+const SUPPORTED_VERSIONS: Range<u32> = 5..7;
+
+pub struct Mapping {
+    files: Vec<String>,
+    functions: Vec<Function>,
+}
+
+impl Mapping {
+    /// Decodes the mapping of the functions with counters
+    ///
+    /// `counters` maps the name hash and structural hash of each function to the range of its
+    /// counters in the global counter array.
+    pub fn parse(
+        covmap: &[u8],
+        covfun: &[u8],
+        counters: &HashMap<(u64, u64), Range<usize>>,
+    ) -> io::Result<Self> {
+        let mut mapping = Self {
+            files: vec![],
+            functions: vec![],
+        };
+        let filenames = mapping.read_filenames(covmap)?;
+        mapping.read_functions(covfun, &filenames, counters)?;
+        Ok(mapping)
+    }
+
+    /// Reads the filenames of each translation unit, keyed by the hash of their encoding
+    fn read_filenames(&mut self, mut covmap: &[u8]) -> io::Result<HashMap<u64, Vec<usize>>> {
+        let mut ids = HashMap::new();
+        let mut tables = HashMap::new();
+
+        while !covmap.is_empty() {
+            let mut header = Reader(covmap);
+            let _records = header.u32()?;
+            let len = header.u32()? as usize;
+            let _coverage_len = header.u32()?;
+            let version = header.u32()?;
+
+            if !SUPPORTED_VERSIONS.contains(&version) {
+                return Err(unsupported(format!(
+                    "coverage mapping version {} isn't supported, only versions {} to {} are",
+                    version + 1,
+                    SUPPORTED_VERSIONS.start + 1,
+                    SUPPORTED_VERSIONS.end,
+                )));
+            }
+
+            let encoded = header.bytes(len)?;
+            let table = decode_filenames(encoded)?
+                .into_iter()
+                .map(|file| {
+                    *ids.entry(file).or_insert_with_key(|file: &String| {
+                        self.files.push(file.clone());
+                        self.files.len() - 1
+                    })
+                })
+                .collect();
+            tables.insert(hash(encoded), table);
+
+            covmap = covmap.get(align(16 + len)..).unwrap_or_default();
+        }
+
+        Ok(tables)
+    }
+
+    fn read_functions(
+        &mut self,
+        mut covfun: &[u8],
+        filenames: &HashMap<u64, Vec<usize>>,
+        counters: &HashMap<(u64, u64), Range<usize>>,
+    ) -> io::Result<()> {
+        while !covfun.is_empty() {
+            let mut header = Reader(covfun);
+            let name = header.u64()?;
+            let len = header.u32()? as usize;
+            let hash = header.u64()?;
+            let filenames_ref = header.u64()?;
+            let data = header.bytes(len)?;
+
+            covfun = covfun.get(align(28 + len)..).unwrap_or_default();
+
+            // functions that were never codegen'd don't have any counters
+            let Some(counters) = counters.get(&(name, hash)) else {
+                continue;
+            };
+            let filenames = filenames
+                .get(&filenames_ref)
+                .ok_or_else(|| invalid("unknown filenames reference"))?;
+
+            self.functions
+                .push(Function::decode(data, filenames, counters.clone())?);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the lines executed according to the given counters
+    pub fn lines(&self, counters: &[u64]) -> Lines {
+        let mut lines = BTreeMap::<usize, BTreeSet<u32>>::new();
+
+        for function in &self.functions {
+            let Some(counters) = counters.get(function.counters.clone()) else {
+                continue;
+            };
+
+            // skip the functions that weren't called
+            if counters.iter().all(|count| *count == 0) {
+                continue;
+            }
+
+            let counts: Vec<_> = function
+                .regions
+                .iter()
+                .map(|counter| function.evaluate(*counter, counters))
+                .collect();
+
+            for line in &function.lines {
+                if line.regions.iter().any(|region| counts[*region] > 0) {
+                    lines.entry(line.file).or_default().insert(line.line);
+                }
+            }
+        }
+
+        lines
+            .into_iter()
+            .map(|(file, lines)| (self.files[file].clone(), lines.into_iter().collect()))
+            .collect()
+    }
+}
+
+struct Function {
+    /// The range of the function's counters in the global counter array
+    counters: Range<usize>,
+    expressions: Vec<(Counter, Counter)>,
+    /// The counter for each code region
+    regions: Vec<Counter>,
+    lines: Vec<Line>,
+}
+
+/// A source line, which is executed if any of its regions are
+struct Line {
+    file: usize,
+    line: u32,
+    regions: Vec<usize>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Counter {
+    Zero,
+    Reference(usize),
+    Subtract(usize),
+    Add(usize),
+}
+
+struct Region {
+    file: usize,
+    start: (u32, u32),
+    end_line: u32,
+}
+
+impl Function {
+    fn decode(data: &[u8], filenames: &[usize], counters: Range<usize>) -> io::Result<Self> {
+        let mut data = Reader(data);
+
+        let files = (0..data.uleb()?)
+            .map(|_| {
+                filenames
+                    .get(data.uleb()? as usize)
+                    .copied()
+                    .ok_or_else(|| invalid("invalid filename index"))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let expressions = (0..data.uleb()?)
+            .map(|_| Ok((data.counter()?, data.counter()?)))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let mut code_regions = vec![];
+        let mut regions = vec![];
+
+        for file in files {
+            let mut line_start = 0;
+            for _ in 0..data.uleb()? {
+                let encoded = data.uleb()?;
+                let mut counter = None;
+
+                if encoded & 0b11 != 0 {
+                    counter = Some(Counter::decode(encoded));
+                } else if encoded & 0b100 == 0 {
+                    // other than plain code regions, the region kinds are only used to
+                    // know how many fields to skip
+                    match encoded >> 3 {
+                        // code region with a zero counter
+                        0 => counter = Some(Counter::Zero),
+                        // skipped region
+                        2 => {}
+                        // branch region
+                        4 => {
+                            data.counter()?;
+                            data.counter()?;
+                        }
+                        // MC/DC decision region
+                        5 => {
+                            data.uleb()?;
+                            data.uleb()?;
+                        }
+                        // MC/DC branch region
+                        6 => {
+                            data.counter()?;
+                            data.counter()?;
+                            data.uleb()?;
+                            data.uleb()?;
+                            data.uleb()?;
+                        }
+                        kind => return Err(invalid(format!("unknown region kind {kind}"))),
+                    }
+                }
+
+                line_start += data.uleb()? as u32;
+                let column_start = data.uleb()? as u32;
+                let line_end = line_start + data.uleb()? as u32;
+                let column_end = data.uleb()?;
+                let is_gap = column_end & (1 << 31) != 0;
+
+                if let (Some(counter), false) = (counter, is_gap) {
+                    code_regions.push(Region {
+                        file,
+                        start: (line_start, column_start),
+                        end_line: line_end,
+                    });
+                    regions.push(counter);
+                }
+            }
+        }
+
+        Ok(Self {
+            counters,
+            expressions,
+            regions,
+            lines: Line::from_regions(&code_regions),
+        })
+    }
+
+    fn evaluate(&self, counter: Counter, counters: &[u64]) -> u64 {
+        let expression = |id: usize| self.expressions.get(id).copied();
+        match counter {
+            Counter::Zero => 0,
+            Counter::Reference(id) => counters.get(id).copied().unwrap_or(0),
+            Counter::Subtract(id) => expression(id).map_or(0, |(lhs, rhs)| {
+                self.evaluate(lhs, counters)
+                    .saturating_sub(self.evaluate(rhs, counters))
+            }),
+            Counter::Add(id) => expression(id).map_or(0, |(lhs, rhs)| {
+                self.evaluate(lhs, counters)
+                    .saturating_add(self.evaluate(rhs, counters))
+            }),
+        }
+    }
+}
+
+impl Line {
+    /// Determines which regions decide if a line is executed
+    ///
+    /// Similar to `llvm-cov`, a line is executed if any region starting on it was executed,
+    /// or if the innermost region spanning it from a previous line was.
+    fn from_regions(regions: &[Region]) -> Vec<Self> {
+        let mut lines = BTreeMap::<(usize, u32), Vec<usize>>::new();
+
+        for (idx, region) in regions.iter().enumerate() {
+            lines
+                .entry((region.file, region.start.0))
+                .or_default()
+                .push(idx);
+
+            for line in region.start.0 + 1..=region.end_line {
+                lines.entry((region.file, line)).or_default();
+            }
+        }
+
+        lines
+            .into_iter()
+            .map(|((file, line), mut starts)| {
+                let wrapped = regions
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, region)| {
+                        region.file == file && region.start.0 < line && line <= region.end_line
+                    })
+                    .max_by_key(|(_, region)| region.start)
+                    .map(|(idx, _)| idx);
+
+                starts.extend(wrapped);
+
+                Self {
+                    file,
+                    line,
+                    regions: starts,
+                }
+            })
+            .collect()
+    }
+}
+
+impl Counter {
+    fn decode(value: u64) -> Self {
+        let id = (value >> 2) as usize;
+        match value & 0b11 {
+            1 => Self::Reference(id),
+            2 => Self::Subtract(id),
+            3 => Self::Add(id),
+            _ => Self::Zero,
+        }
+    }
+}
+
+fn decode_filenames(encoded: &[u8]) -> io::Result<Vec<String>> {
+    let mut encoded = Reader(encoded);
+    let count = encoded.uleb()?;
+    let _uncompressed_len = encoded.uleb()?;
+    let compressed_len = encoded.uleb()? as usize;
+
+    let decompressed;
+    let mut names = if compressed_len > 0 {
+        decompressed = miniz_oxide::inflate::decompress_to_vec_zlib(encoded.bytes(compressed_len)?)
+            .map_err(|err| invalid(format!("{err:?}")))?;
+        Reader(&decompressed)
+    } else {
+        encoded
+    };
+
+    let mut filenames = vec![];
+    for _ in 0..count {
+        let len = names.uleb()? as usize;
+        let name = String::from_utf8_lossy(names.bytes(len)?).into_owned();
+        filenames.push(name);
+    }
+
+    // relative paths are relative to the compilation directory, which is always first
+    if let Some((dir, names)) = filenames.split_first_mut() {
+        let dir = std::path::Path::new(dir);
+        for name in names {
+            if std::path::Path::new(name).is_relative() {
+                *name = dir.join(&*name).display().to_string();
+            }
+        }
+    }
+
+    Ok(filenames)
+}
+
+/// Computes the hash LLVM uses to reference the filenames of a translation unit
+fn hash(bytes: &[u8]) -> u64 {
+    let digest = md5::compute(bytes);
+    u64::from_le_bytes(digest.0[..8].try_into().unwrap())
+}
+
+fn align(len: usize) -> usize {
+    (len + 7) & !7
+}
+
+pub fn invalid<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// Returns an error for a format that's valid but can't be decoded by this version of bolero
+pub fn unsupported<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, error)
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(invalid("unexpected end of coverage mapping"));
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_ne_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_ne_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+
+    fn uleb(&mut self) -> io::Result<u64> {
+        let mut value = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.bytes(1)?[0];
+            if shift < 64 {
+                value |= u64::from(byte & 0x7f) << shift;
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn counter(&mut self) -> io::Result<Counter> {
+        Ok(Counter::decode(self.uleb()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The fixtures were extracted from `fixtures/branch.rs` on x86_64 Linux with:
+    //
+    //   rustc -Cinstrument-coverage --remap-path-prefix=$PWD=/fixture branch.rs
+    //   objcopy --dump-section __llvm_covmap=covmap.bin --dump-section __llvm_covfun=covfun.bin branch
+    const COVMAP: &[u8] = include_bytes!("fixtures/covmap.bin");
+    const COVFUN: &[u8] = include_bytes!("fixtures/covfun.bin");
+
+    fn parse() -> Mapping {
+        // `main` is the only function and its counters come first
+        let mut header = Reader(COVFUN);
+        let name = header.u64().unwrap();
+        let _len = header.u32().unwrap();
+        let hash = header.u64().unwrap();
+
+        let counters = HashMap::from([((name, hash), 0..2)]);
+        Mapping::parse(COVMAP, COVFUN, &counters).unwrap()
+    }
+
+    #[test]
+    fn parse_test() {
+        let mapping = parse();
+        assert_eq!(mapping.files, ["/fixture", "/fixture/branch.rs"]);
+        assert_eq!(mapping.functions.len(), 1);
+
+        // the function wasn't called
+        assert!(mapping.lines(&[0, 0]).is_empty());
+
+        let file = "/fixture/branch.rs".to_string();
+        let then_branch = Lines::from([(file.clone(), vec![1, 2, 3, 4, 5, 8])]);
+        assert_eq!(mapping.lines(&[1, 1]), then_branch);
+        let else_branch = Lines::from([(file, vec![1, 2, 3, 5, 6, 7, 8])]);
+        assert_eq!(mapping.lines(&[1, 0]), else_branch);
+    }
+
+    #[test]
+    fn unsupported_version_test() {
+        let mut covmap = COVMAP.to_vec();
+        covmap[12..16].copy_from_slice(&7u32.to_ne_bytes());
+
+        let err = Mapping::parse(&covmap, COVFUN, &HashMap::new())
+            .err()
+            .expect("version 8 isn't supported");
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+        assert!(err.to_string().contains("version 8"), "{err}");
+    }
+}
@@ -0,0 +1,95 @@
+//! Shrinking for the frame state of the exhaustive driver
+//!
+//! Each frame holds the value selected by one of the driver's choices. Lowering a frame selects a
+//! smaller value, and dropping the trailing frames makes the remaining choices select their
+//! lowest values.
+
+use std::time::{Duration, Instant};
+
+/// Shrinks `frames` while `fails` returns `true` for the candidate
+///
+/// `frames` should already fail the test. The smallest failing frames are returned.
+pub fn shrink<F>(mut frames: Vec<u64>, shrink_time: Duration, mut fails: F) -> Vec<u64>
+where
+    F: FnMut(&[u64]) -> bool,
+{
+    let start_time = Instant::now();
+    let mut candidate = vec![];
+
+    while start_time.elapsed() < shrink_time {
+        let mut was_changed = false;
+
+        // drop the trailing frames
+        if let Some(len) = (0..frames.len()).find(|len| fails(&frames[..*len])) {
+            frames.truncate(len);
+            was_changed = true;
+        }
+
+        // bisect each of the frame values towards 0
+        for idx in 0..frames.len() {
+            let (mut passing, mut failing) = (0, frames[idx]);
+
+            // try the lowest value before bisecting
+            candidate.clone_from(&frames);
+            candidate[idx] = 0;
+            if failing > 0 && fails(&candidate) {
+                failing = 0;
+            }
+
+            while passing + 1 < failing && start_time.elapsed() < shrink_time {
+                let value = passing + (failing - passing) / 2;
+                candidate[idx] = value;
+                if fails(&candidate) {
+                    failing = value;
+                } else {
+                    passing = value;
+                }
+            }
+
+            was_changed |= failing != frames[idx];
+            frames[idx] = failing;
+        }
+
+        if !was_changed {
+            break;
+        }
+    }
+
+    frames
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shrink_test(frames: &[u64], fails: impl FnMut(&[u64]) -> bool) -> Vec<u64> {
+        shrink(frames.to_vec(), Duration::from_secs(1), fails)
+    }
+
+    #[test]
+    fn lower_test() {
+        let frames = shrink_test(&[200, 255, 3], |frames| {
+            frames.first().copied().unwrap_or(0) > 100
+        });
+        assert_eq!(frames, [101]);
+
+        let frames = shrink_test(&[7, 255, 3], |frames| {
+            frames.get(1).copied().unwrap_or(0) >= 10
+        });
+        assert_eq!(frames, [0, 10]);
+    }
+
+    #[test]
+    fn trailing_test() {
+        // dropped frames select 0, which still fails
+        let frames = shrink_test(&[1, 2, 3], |frames| frames.len() < 10);
+        assert!(frames.is_empty());
+    }
+
+    #[test]
+    fn sum_test() {
+        let frames = shrink_test(&[255, 255, 255], |frames| frames.iter().sum::<u64>() >= 300);
+        assert_eq!(frames.iter().sum::<u64>(), 300);
+        assert_eq!(frames, [45, 255]);
+    }
+}
@@ -1,5 +1,6 @@
 #![cfg_attr(fuzzing_random, allow(dead_code))]
 
+use super::token::{Kind, Token};
 use bolero_engine::{rng::Recommended as Rng, Seed};
 use bolero_generator::{driver, TypeGenerator};
 use rand::SeedableRng;
@@ -10,17 +11,73 @@ pub use bolero_engine::input::*;
 pub enum Test {
     File(FileTest),
     Rng(RngTest),
+    Token(Token),
 }
 
 impl Test {
+    /// Returns the test for a file, which is replayed as a token if it has a `.token` extension
+    pub fn file(path: PathBuf) -> Self {
+        if path.extension().map_or(false, |ext| ext == "token") {
+            let token = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|token| token.trim().parse::<Token>().ok())
+                // exhaustive tokens are only replayed by the exhaustive driver
+                .filter(|token| token.kind != Kind::Exhaustive);
+            if let Some(token) = token {
+                return Self::Token(token);
+            }
+        }
+
+        Self::File(FileTest { path })
+    }
+
     pub fn seed(&self) -> Option<Seed> {
         match self {
-            Test::File(_) => None,
+            Test::File(_) | Test::Token(_) => None,
             Test::Rng(t) => Some(t.seed),
         }
     }
 }
 
+/// Where a test input came from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Origin {
+    /// A file in the `crashes` directory
+    Crashes,
+    /// A file in the `afl_state/crashes` directory
+    AflCrashes,
+    /// A file in the `afl_state/hangs` directory
+    AflHangs,
+    /// A file in the `corpus` directory
+    Corpus,
+    /// A file in the `afl_state/queue` directory
+    AflQueue,
+    /// A seed provided with `BOLERO_RANDOM_SEED`
+    Seed,
+    /// A randomly generated seed
+    Rng,
+    /// An input shared by another `cargo bolero test --jobs` worker
+    Shared,
+    /// A token provided with `BOLERO_FAILURE_TOKEN`
+    Token,
+    /// A file provided with `BOLERO_REPLAY_FILE`
+    Replay,
+}
+
+impl Origin {
+    /// The directories containing the test files, relative to the target work dir
+    pub fn dirs(self) -> &'static [&'static str] {
+        match self {
+            Self::Crashes => &["crashes"],
+            Self::AflCrashes => &["afl_state", "crashes"],
+            Self::AflHangs => &["afl_state", "hangs"],
+            Self::Corpus => &["corpus"],
+            Self::AflQueue => &["afl_state", "queue"],
+            Self::Seed | Self::Rng | Self::Shared | Self::Token | Self::Replay => &[],
+        }
+    }
+}
+
 pub struct FileTest {
     pub path: PathBuf,
 }
@@ -69,20 +126,37 @@ impl RngTest {
     ) -> RngBufferedInput<'a> {
         let rng = self.rng();
         let driver = BufferedRng { rng, buffer };
-        let driver = driver::Rng::new(driver, options);
-        RngBufferedInput {
-            driver,
-            slice: vec![],
-        }
+        RngInput::new(driver, options)
+    }
+
+    /// Returns a driver that records the bytes returned by the rng
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn buffered_driver(&self, options: &driver::Options) -> driver::Rng<BufferedRng<Vec<u8>>> {
+        let rng = self.rng();
+        let driver = BufferedRng {
+            rng,
+            buffer: vec![],
+        };
+        driver::Rng::new(driver, options)
     }
 }
 
-pub struct BufferedRng<'a> {
+pub struct BufferedRng<B> {
     rng: Rng,
-    buffer: &'a mut Vec<u8>,
+    buffer: B,
 }
 
-impl rand::RngCore for BufferedRng<'_> {
+#[cfg(feature = "std")]
+impl BufferedRng<Vec<u8>> {
+    /// Returns the bytes returned by the rng so far
+    #[inline]
+    pub fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+}
+
+impl<B: AsMut<Vec<u8>>> rand::RngCore for BufferedRng<B> {
     fn next_u32(&mut self) -> u32 {
         let mut data = [0; 4];
         self.fill_bytes(&mut data);
@@ -97,17 +171,31 @@ impl rand::RngCore for BufferedRng<'_> {
 
     fn fill_bytes(&mut self, bytes: &mut [u8]) {
         self.rng.fill_bytes(bytes);
-        self.buffer.extend_from_slice(bytes);
+        self.buffer.as_mut().extend_from_slice(bytes);
     }
 }
 
-pub struct RngBufferedInput<'a> {
-    driver: driver::Rng<BufferedRng<'a>>,
+/// A test input driven by a custom rng
+pub struct RngInput<R: rand::RngCore> {
+    driver: driver::Rng<R>,
     slice: Vec<u8>,
 }
 
-impl<'a, Output> Input<Output> for RngBufferedInput<'a> {
-    type Driver = driver::Rng<BufferedRng<'a>>;
+impl<R: rand::RngCore> RngInput<R> {
+    #[inline]
+    pub fn new(rng: R, options: &driver::Options) -> Self {
+        Self {
+            driver: driver::Rng::new(rng, options),
+            slice: vec![],
+        }
+    }
+}
+
+impl<R: rand::RngCore, Output> Input<Output> for RngInput<R>
+where
+    driver::Rng<R>: bolero_generator::Driver + core::panic::RefUnwindSafe,
+{
+    type Driver = driver::Rng<R>;
 
     fn with_slice<F: FnMut(&[u8]) -> Output>(&mut self, f: &mut F) -> Output {
         self.slice.mutate(&mut self.driver);
@@ -119,11 +207,32 @@ impl<'a, Output> Input<Output> for RngBufferedInput<'a> {
     }
 }
 
-pub struct ReplayRng<'a> {
-    buffer: &'a [u8],
+/// Records the bytes returned by the rng so they can be replayed with [`ReplayRng`]
+pub type RngBufferedInput<'a> = RngInput<BufferedRng<&'a mut Vec<u8>>>;
+
+/// Replays the bytes recorded by [`RngBufferedInput`]
+pub type RngReplayedInput<'a> = RngInput<ReplayRng<&'a [u8]>>;
+
+pub struct ReplayRng<B> {
+    buffer: B,
+    offset: usize,
+}
+
+impl<B: AsRef<[u8]>> ReplayRng<B> {
+    #[inline]
+    pub fn new(buffer: B) -> Self {
+        Self { buffer, offset: 0 }
+    }
+}
+
+impl<'a> ReplayRng<&'a [u8]> {
+    #[inline]
+    pub fn input(buffer: &'a [u8], options: &driver::Options) -> RngReplayedInput<'a> {
+        RngInput::new(Self::new(buffer), options)
+    }
 }
 
-impl rand::RngCore for ReplayRng<'_> {
+impl<B: AsRef<[u8]>> rand::RngCore for ReplayRng<B> {
     fn next_u32(&mut self) -> u32 {
         let mut data = [0; 4];
         self.fill_bytes(&mut data);
@@ -137,33 +246,35 @@ impl rand::RngCore for ReplayRng<'_> {
     }
 
     fn fill_bytes(&mut self, bytes: &mut [u8]) {
-        let len = self.buffer.len().min(bytes.len());
-        let (copy_from, remaining) = self.buffer.split_at(len);
+        let buffer = &self.buffer.as_ref()[self.offset..];
+        let len = buffer.len().min(bytes.len());
         let (copy_to, fill_to) = bytes.split_at_mut(len);
-        copy_to.copy_from_slice(copy_from);
+        copy_to.copy_from_slice(&buffer[..len]);
         fill_to.fill(0);
-        self.buffer = remaining;
+        self.offset += len;
     }
 }
 
+#[cfg(feature = "std")]
 pub struct RngReplayInput<'a> {
     pub buffer: &'a mut Vec<u8>,
 }
 
+#[cfg(feature = "std")]
 impl bolero_engine::shrink::Input for RngReplayInput<'_> {
     type Driver<'d>
-        = driver::Rng<ReplayRng<'d>>
+        = driver::Rng<ReplayRng<&'d [u8]>>
     where
         Self: 'd;
 
     #[inline]
     fn driver(&self, len: usize, options: &driver::Options) -> Self::Driver<'_> {
-        let buffer = &self.buffer[..len];
-        let rng = ReplayRng { buffer };
+        let rng = ReplayRng::new(&self.buffer[..len]);
         driver::Rng::new(rng, options)
     }
 }
 
+#[cfg(feature = "std")]
 impl AsRef<Vec<u8>> for RngReplayInput<'_> {
     #[inline]
     fn as_ref(&self) -> &Vec<u8> {
@@ -171,6 +282,7 @@ impl AsRef<Vec<u8>> for RngReplayInput<'_> {
     }
 }
 
+#[cfg(feature = "std")]
 impl AsMut<Vec<u8>> for RngReplayInput<'_> {
     #[inline]
     fn as_mut(&mut self) -> &mut Vec<u8> {
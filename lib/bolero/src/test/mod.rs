@@ -1,32 +1,37 @@
 #![cfg_attr(fuzzing_random, allow(dead_code))]
 
+use crate::observe;
 use bolero_engine::{
     driver::{self, exhaustive, object::Object},
-    rng, Engine, Failure, Seed, TargetLocation, Test,
+    rng,
+    shrink::ShrinkPass,
+    Engine, Failure, Seed, TargetLocation, Test,
 };
+use bolero_generator::rejection;
 use core::{fmt, mem::size_of, time::Duration};
-use std::path::PathBuf;
-use std::env;
+use std::{
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+use token::Token;
 type ExhastiveDriver = Box<Object<exhaustive::Driver>>;
-use std::collections::HashMap;
-use std::sync::Mutex;
-
-
 
+mod checkpoint;
+mod coverage;
+mod frames;
+mod observation;
 mod outcome;
+mod reduce;
 
 mod input;
 mod report;
-
-
-/*pub fn event_with_payload<T: ToString>(key: &str, value: T) {
-    let mut context = GLOBAL_CONTEXT.lock().unwrap();
-    if let Some(map) = context.as_mut() {
-        map.insert(key.to_string(), value.to_string());
-    }
-}
-
-*/
+#[cfg(feature = "std")]
+mod scoped;
+mod share;
+mod shrink;
+mod statistics;
+mod token;
+mod watchdog;
 
 /// Engine implementation which mimics Rust's default test
 /// harness. By default, the test inputs will include any present
@@ -35,11 +40,51 @@ mod report;
 pub struct TestEngine {
     location: TargetLocation,
     rng_cfg: rng::Options,
+    tyche: Option<PathBuf>,
+    /// The directory where shrunken rng failures are saved, if enabled
+    crashes: Option<PathBuf>,
+    /// The directory where inputs that exceed the timeout are saved, if enabled
+    hangs: Option<PathBuf>,
+    /// A failure token provided with `BOLERO_FAILURE_TOKEN`
+    token: Option<Token>,
+    /// The exhaustive state provided with `BOLERO_EXHAUSTIVE_RESUME`
+    resume: Option<Vec<u64>>,
+    /// The share of an exhaustive enumeration run by this worker of `cargo bolero test --jobs`
+    shard: Option<(usize, usize)>,
+    /// The directory where the progress of exhaustive runs is saved, if enabled
+    checkpoints: Option<PathBuf>,
+    /// The shrinking options provided with `BOLERO_SHRINK_TIME_MS` and `BOLERO_SHRINK_JOBS`
+    shrink_options: driver::Options,
+    /// Set when running a single shrinking candidate for a parent process
+    is_candidate: bool,
+    /// The passes added with `TestTarget::with_shrink_pass`
+    shrink_passes: Vec<Box<dyn ShrinkPass>>,
+    /// A file provided with `BOLERO_REPLAY_FILE`
+    replay_file: Option<PathBuf>,
+    /// Set with `BOLERO_REPLAY=1` to only run the inputs that were provided explicitly
+    is_replay: bool,
+    /// Set when describing a hang for a parent process, which generates the input without testing it
+    is_describe: bool,
+    /// The corpus inputs provided by `cargo bolero reduce` with `BOLERO_REDUCE_INPUTS`
+    reduce: Option<reduce::Reduce>,
+    /// The coverage requirements added with `TestTarget::with_cover`
+    covers: Vec<(String, f64)>,
+}
+
+/// A failed test case
+struct Failed {
+    /// The failure report printed before panicking
+    message: String,
+    /// The `Debug` representation of the failing input
+    representation: String,
+    /// The `Debug` representation of the shrunken input, if the failure was shrunk
+    shrunken: Option<String>,
 }
 
 struct NamedTest {
     name: String,
     data: input::Test,
+    origin: input::Origin,
 }
 
 impl fmt::Display for NamedTest {
@@ -52,25 +97,186 @@ impl fmt::Display for NamedTest {
     }
 }
 
-impl From<input::RngTest> for NamedTest {
+impl NamedTest {
     #[inline]
-    fn from(value: input::RngTest) -> Self {
+    fn rng(test: input::RngTest, origin: input::Origin) -> Self {
         Self {
             name: String::new(),
-            data: input::Test::Rng(value),
+            data: input::Test::Rng(test),
+            origin,
         }
     }
+
+    /// Describes where the input came from
+    fn source(&self) -> String {
+        match (&self.data, self.origin) {
+            (input::Test::Rng(test), input::Origin::Seed) => {
+                format!("BOLERO_RANDOM_SEED={}", test.seed)
+            }
+            (input::Test::Rng(test), _) => format!("seed {}", test.seed),
+            (_, input::Origin::Shared) => format!("{} shared by another worker", self.name),
+            (input::Test::Token(_), _) => "BOLERO_FAILURE_TOKEN".to_string(),
+            (input::Test::File(_), input::Origin::Replay) => {
+                format!("BOLERO_REPLAY_FILE {}", self.name)
+            }
+            (input::Test::File(_), origin) => {
+                let dir = origin.dirs().join("/");
+                format!("{dir} file {}", self.name)
+            }
+        }
+    }
+
+    /// Describes how the input was generated for Tyche observations
+    fn how_generated(&self) -> String {
+        match self.origin {
+            input::Origin::Rng => format!("generated from {}", self.source()),
+            _ => format!("replayed from {}", self.source()),
+        }
+    }
+
+    /// Describes how the shrunken input was generated for Tyche observations
+    fn how_shrunk(&self) -> String {
+        format!("shrunk from {}", self.source())
+    }
 }
 
 impl TestEngine {
     #[allow(dead_code)]
     pub fn new(location: TargetLocation) -> Self {
+        // a parallel shrink runs each candidate in its own process
+        let candidate = std::env::var("BOLERO_SHRINK_CANDIDATE")
+            .ok()
+            .filter(|token| !token.is_empty())
+            .map(|token| {
+                token
+                    .parse::<Token>()
+                    .unwrap_or_else(|err| panic!("invalid BOLERO_SHRINK_CANDIDATE: {err}"))
+            });
+        let is_candidate = candidate.is_some();
+
+        let mut shrink_options = driver::Options::default();
+        let var = |name| std::env::var(name).ok()?.parse::<u64>().ok();
+        if let Some(shrink_time) = var("BOLERO_SHRINK_TIME_MS") {
+            shrink_options.set_shrink_time(Duration::from_millis(shrink_time));
+        }
+        if let Some(shrink_jobs) = var("BOLERO_SHRINK_JOBS") {
+            shrink_options.set_shrink_jobs(shrink_jobs as usize);
+        }
+        // the parent is already shrinking the failure
+        if is_candidate {
+            shrink_options.set_shrink_time(Duration::ZERO);
+        }
+
         Self {
-            location,
             rng_cfg: Default::default(),
+            tyche: std::env::var_os("BOLERO_TYCHE")
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from),
+            crashes: crashes_dir(&location),
+            hangs: hangs_dir(&location),
+            token: candidate.or_else(|| {
+                std::env::var("BOLERO_FAILURE_TOKEN")
+                    .ok()
+                    .filter(|token| !token.is_empty())
+                    .map(|token| {
+                        token
+                            .parse()
+                            .unwrap_or_else(|err| panic!("invalid BOLERO_FAILURE_TOKEN: {err}"))
+                    })
+            }),
+            resume: std::env::var("BOLERO_EXHAUSTIVE_RESUME")
+                .ok()
+                .filter(|token| !token.is_empty())
+                .map(|token| {
+                    token
+                        .parse::<Token>()
+                        .and_then(|token| token.frames())
+                        .unwrap_or_else(|err| panic!("invalid BOLERO_EXHAUSTIVE_RESUME: {err}"))
+                }),
+            shard: worker_shard(),
+            checkpoints: checkpoints_dir(&location),
+            shrink_options,
+            is_candidate,
+            shrink_passes: vec![],
+            replay_file: std::env::var_os("BOLERO_REPLAY_FILE")
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from),
+            is_replay: std::env::var("BOLERO_REPLAY").as_deref() == Ok("1"),
+            is_describe: std::env::var("BOLERO_DESCRIBE_HANG").as_deref() == Ok("1"),
+            reduce: reduce::Reduce::new(),
+            covers: vec![],
+            location,
         }
     }
 
+    /// Writes a Tyche observation for each test case to the given path
+    #[cfg(test)]
+    pub(crate) fn with_tyche_path(&mut self, path: PathBuf) -> &mut Self {
+        self.tyche = Some(path);
+        self
+    }
+
+    /// Replays the given failure token before any other inputs
+    #[cfg(test)]
+    pub(crate) fn with_token(&mut self, token: &str) -> &mut Self {
+        self.token = Some(token.parse().unwrap());
+        self
+    }
+
+    /// Resumes the exhaustive enumeration from the given token
+    #[cfg(test)]
+    pub(crate) fn with_resume(&mut self, token: &str) -> &mut Self {
+        self.resume = Some(token.parse::<Token>().unwrap().frames().unwrap());
+        self
+    }
+
+    /// Saves the progress of exhaustive runs to the given directory
+    #[cfg(test)]
+    pub(crate) fn with_checkpoints_dir(&mut self, path: PathBuf) -> &mut Self {
+        self.checkpoints = Some(path);
+        self
+    }
+
+    /// Saves shrunken rng failures to the given directory
+    #[cfg(test)]
+    pub(crate) fn with_crashes_dir(&mut self, path: PathBuf) -> &mut Self {
+        self.crashes = Some(path);
+        self
+    }
+
+    /// Runs the given seed before any generated inputs
+    #[cfg(test)]
+    pub(crate) fn with_seed(&mut self, seed: Seed) -> &mut Self {
+        self.rng_cfg.seed = Some(seed);
+        self
+    }
+
+    /// Describes the replayed input as a hang, saving it to the given directory
+    #[cfg(test)]
+    pub(crate) fn with_describe_hang(&mut self, hangs: PathBuf) -> &mut Self {
+        self.hangs = Some(hangs);
+        self.is_describe = true;
+        self
+    }
+
+    /// Only runs the given file
+    #[cfg(test)]
+    pub(crate) fn with_replay_file(&mut self, path: PathBuf) -> &mut Self {
+        self.replay_file = Some(path);
+        self.is_replay = true;
+        self
+    }
+
+    pub fn with_shrink_pass(&mut self, pass: Box<dyn ShrinkPass>) -> &mut Self {
+        self.shrink_passes.push(pass);
+        self
+    }
+
+    pub fn with_cover(&mut self, label: &str, min_percent: f64) -> &mut Self {
+        self.covers.push((label.to_string(), min_percent));
+        self
+    }
+
     pub fn with_test_time(&mut self, test_time: Duration) -> &mut Self {
         self.rng_cfg.test_time = self.rng_cfg.test_time.or(Some(test_time));
         self
@@ -97,11 +303,8 @@ impl TestEngine {
         fuzz_target_path
     }
 
-    fn file_tests<'a, D: Iterator<Item = &'a str> + std::panic::UnwindSafe>(
-        &self,
-        sub_dirs: D,
-    ) -> impl Iterator<Item = NamedTest> {
-        std::fs::read_dir(self.sub_dir(sub_dirs))
+    fn file_tests(&self, origin: input::Origin) -> impl Iterator<Item = NamedTest> {
+        std::fs::read_dir(self.sub_dir(origin.dirs().iter().cloned()))
             .ok()
             .into_iter()
             .flat_map(move |dir| {
@@ -111,7 +314,8 @@ impl TestEngine {
                     .filter(|path| !path.file_name().unwrap().to_str().unwrap().starts_with('.'))
                     .map(move |path| NamedTest {
                         name: format!("{}", path.display()),
-                        data: input::Test::File(input::FileTest { path }),
+                        data: input::Test::file(path),
+                        origin,
                     })
             })
     }
@@ -138,32 +342,71 @@ impl TestEngine {
         })
     }
 
+    /// Returns the exhaustive state of the failure token, if one was provided
+    fn exhaustive_token(&self) -> Option<Vec<u64>> {
+        let token = self.token.as_ref()?;
+        token.frames().ok()
+    }
+
+    fn token_tests(&self) -> impl Iterator<Item = NamedTest> {
+        self.token.clone().into_iter().map(|token| NamedTest {
+            name: String::new(),
+            data: input::Test::Token(token),
+            origin: input::Origin::Token,
+        })
+    }
+
+    /// Returns the inputs that were provided explicitly
+    fn replay_tests(&self) -> impl Iterator<Item = NamedTest> {
+        self.token_tests()
+            .chain(
+                self.seed_tests()
+                    .map(|t| NamedTest::rng(t, input::Origin::Seed)),
+            )
+            .chain(self.replay_file.clone().map(|path| NamedTest {
+                name: format!("{}", path.display()),
+                data: input::Test::file(path),
+                origin: input::Origin::Replay,
+            }))
+    }
+
     #[cfg(fuzzing_random)]
     fn tests(&self) -> impl Iterator<Item = NamedTest> {
-        self.seed_tests()
-            .map(|t| t.into())
-            .chain(self.rng_tests().map(|t| t.into()))
+        self.replay_tests().chain(
+            self.rng_tests()
+                .map(|t| NamedTest::rng(t, input::Origin::Rng)),
+        )
     }
 
     #[cfg(not(fuzzing_random))]
     fn tests(&self) -> impl Iterator<Item = NamedTest> {
-        self.seed_tests()
-            .map(|t| t.into())
-            .chain(self.file_tests(["crashes"].iter().cloned()))
-            .chain(self.file_tests(["afl_state", "crashes"].iter().cloned()))
-            .chain(self.file_tests(["afl_state", "hangs"].iter().cloned()))
-            .chain(self.file_tests(["corpus"].iter().cloned()))
-            .chain(self.file_tests(["afl_state", "queue"].iter().cloned()))
-            .chain(self.rng_tests().map(|t| t.into()))
+        self.replay_tests()
+            .chain(self.file_tests(input::Origin::Crashes))
+            .chain(self.file_tests(input::Origin::AflCrashes))
+            .chain(self.file_tests(input::Origin::AflHangs))
+            .chain(self.file_tests(input::Origin::Corpus))
+            .chain(self.file_tests(input::Origin::AflQueue))
+            .chain(
+                self.rng_tests()
+                    .map(|t| NamedTest::rng(t, input::Origin::Rng)),
+            )
     }
 
-    fn run_with_value<T>(self, test: T, options: driver::Options) -> bolero_engine::Never
+    fn run_with_value<T>(mut self, test: T, mut options: driver::Options) -> bolero_engine::Never
     where
         T: Test,
         T::Value: core::fmt::Debug,
     {
-        if options.exhaustive() {
+        options.merge_from(&self.shrink_options);
+
+        if self.is_describe {
+            return self.describe_hang(test, &options);
+        }
+
+        // exhaustive tokens can only be replayed by the exhaustive driver
+        if (options.exhaustive() && !self.is_replay) || self.exhaustive_token().is_some() {
             let mut buffer = vec![];
+            let tyche_on = self.tyche.is_some();
 
             let testfn = |mut driver: Box<Object<exhaustive::Driver>>, test: &mut T| {
                 let mut input = input::ExhastiveInput {
@@ -171,35 +414,30 @@ impl TestEngine {
                     buffer: &mut buffer,
                 };
                 let mut representation = String::from("");
-                let tyche_on = match env::var("BOLERO_TYCHE") {
-                    Ok(v) => v,
-                    Err(_) => "".to_string(),
-                };
-        
 
                 let result = match test.test(&mut input) {
                     Ok(is_valid) => {
                         // restart the driver to replay what was selected
-                        if tyche_on != "" {
+                        if tyche_on && is_valid {
                             input.driver.replay();
 
-                            
                             let value = test.generate_value(&mut input);
                             representation = format!("{:?}", value);
                         }
                         Ok((is_valid, representation))
                     }
                     Err(error) => {
+                        let token = Token::exhaustive(&input.driver.serialize());
                         // restart the driver to replay what was selected
                         input.driver.replay();
                         let input = test.generate_value(&mut input);
                         let representation = format!("{:?}", input);
-                        let error = Failure {
-                            seed: None,
-                            error,
-                            input,
-                        };
-                        Err((error.to_string(), representation))
+                        let error = Failure::new(error, input, None).with_token(token.to_string());
+                        Err(Failed {
+                            message: error.to_string(),
+                            representation,
+                            shrunken: None,
+                        })
                     }
                 };
 
@@ -215,6 +453,14 @@ impl TestEngine {
         let file_options = &file_options;
         let rng_options = &rng_options;
 
+        let crashes = self.crashes.clone();
+        let passes = core::mem::take(&mut self.shrink_passes);
+        let mut shrinker =
+            shrink::Context::new(&self.location, rng_options, passes, crashes.clone());
+        if let Some(fork) = test.fork() {
+            shrinker = shrinker.with_fork(fork, rng_options);
+        }
+        let shrinker = &shrinker;
         let mut buffer = vec![];
         let mut cache = driver::cache::Cache::default();
         let testfn = |test: &mut T, data: &input::Test| {
@@ -222,101 +468,144 @@ impl TestEngine {
             match data {
                 input::Test::File(file) => {
                     file.read_into(&mut buffer);
-
-                    let mut input = input::Bytes::new(&buffer, file_options);
-
-
-                    let result = test.test(&mut input);
-                    // Generate a value for representation after the test
-                    let mut repr_input = input::Bytes::new(&buffer, file_options);
-                    let value = test.generate_value(&mut repr_input);
-                    let representation = format!("{:?}", value);
-
-
-                    
-                    result.map(|is_valid| (is_valid, representation.clone()))
-                        .map_err(|error| {
-                            let shrunken = test.shrink(buffer.clone(), data.seed(), file_options);
-
-                            if let Some(shrunken) = shrunken {
-                                (format!("{:#}", shrunken), representation)
-                            } else {
-                                (format!(
-                                    "{:#}",
-                                    (Failure {
-                                        seed: data.seed(),
-                                        error,
-                                        input: buffer.clone()
-                                    })
-                                ), representation)
-                            }
-                        })
+                    test_bytes(test, &buffer, file_options, shrinker)
                 }
+                input::Test::Token(token) => match token.kind {
+                    token::Kind::Bytes => test_bytes(test, &token.bytes, file_options, shrinker),
+                    token::Kind::Rng => test_replayed_rng(test, &token.bytes, rng_options),
+                    token::Kind::Exhaustive => unreachable!("replayed by the exhaustive driver"),
+                },
                 input::Test::Rng(conf) => {
                     let mut input = conf.input(&mut buffer, &mut cache, rng_options);
                     let result = test.test(&mut input);
-                    
-                    buffer.clear();
-                    let mut repr_input = conf.input(&mut buffer, &mut cache, rng_options);
-                    let value = test.generate_value(&mut repr_input);
-                    let representation = format!("{:?}", value);
-                    
-                    result.map(|is_valid| (is_valid, representation.clone()))
+
+                    let representation = if matches!(result, Ok(false)) {
+                        String::new()
+                    } else {
+                        buffer.clear();
+                        let mut repr_input = conf.input(&mut buffer, &mut cache, rng_options);
+                        let value = test.generate_value(&mut repr_input);
+                        format!("{:?}", value)
+                    };
+
+                    result
+                        .map(|is_valid| (is_valid, representation.clone()))
                         .map_err(|error| {
+                            // reseed the input and record the rng bytes for the token and shrinking
+                            buffer.clear();
+                            let mut input = conf.buffered_input(&mut buffer, rng_options);
+                            let input = test.generate_value(&mut input);
+                            let token = Token::rng(buffer.clone());
+
                             let shrunken = if rng_options.shrink_time_or_default().is_zero() {
                                 None
                             } else {
-                                // reseed the input and buffer the rng for shrinking
-                                let mut input = conf.buffered_input(&mut buffer, rng_options);
-                                let _ = test.generate_value(&mut input);
-
-                                test.shrink(buffer.clone(), data.seed(), rng_options)
+                                // keep the smallest input on disk in case shrinking is interrupted
+                                let mut best = shrinker.best(token::Kind::Bytes);
+                                let mut on_best = |input: &[u8]| best.save(input);
+                                let hooks = shrinker.hooks(token::Kind::Bytes, Some(&mut on_best));
+
+                                // the shrinker leaves the shrunken bytes in the buffer
+                                let shrunken = suspend_observations(|| {
+                                    bolero_engine::shrink::shrink_with(
+                                        test,
+                                        &mut buffer,
+                                        data.seed(),
+                                        rng_options,
+                                        hooks,
+                                    )
+                                });
+                                shrunken.map(|shrunken| (shrunken, best.finish(&buffer)))
                             };
 
-                            if let Some(shrunken) = shrunken {
-                                (format!("{:#}", shrunken), representation)
+                            if let Some((mut shrunken, saved)) = shrunken {
+                                shrunken.token = Some(Token::bytes(buffer.clone()).to_string());
+                                let mut message = format!("{:#}", shrunken);
+                                if let Some(path) = saved {
+                                    let path = path.display();
+                                    message.push_str(&format!(
+                                        "\nSaved the shrunken input to {path}\n"
+                                    ));
+                                }
+                                Failed {
+                                    message,
+                                    representation,
+                                    shrunken: Some(format!("{:?}", shrunken.input)),
+                                }
                             } else {
-                                buffer.clear();
-                                let mut input = conf.input(&mut buffer, &mut cache, rng_options);
-                                let input = test.generate_value(&mut input);
-                                (format!(
+                                // keep the failure even if it couldn't be shrunk
+                                let mut message = format!(
                                     "{:#}",
-                                    Failure {
-                                        seed: data.seed(),
-                                        error,
-                                        input
-                                    }
-                                ),representation)
+                                    Failure::new(error, input, data.seed())
+                                        .with_token(token.to_string())
+                                );
+                                let saved =
+                                    crashes.as_deref().and_then(|dir| save_input(dir, &token));
+                                if let Some(path) = saved {
+                                    let path = path.display();
+                                    message.push_str(&format!(
+                                        "\nSaved the failing input to {path}\n"
+                                    ));
+                                }
+                                Failed {
+                                    message,
+                                    representation,
+                                    shrunken: None,
+                                }
                             }
                         })
                 }
             }
         };
 
-        self.run_tests(test, testfn)
+        let tokenfn = |test: &mut T, data: &input::Test| {
+            let mut buffer = vec![];
+            match data {
+                input::Test::File(file) => {
+                    file.read_into(&mut buffer);
+                    Token::bytes(buffer)
+                }
+                input::Test::Token(token) => token.clone(),
+                input::Test::Rng(conf) => {
+                    // regenerate the value to record the bytes returned by the rng
+                    let mut input = conf.buffered_input(&mut buffer, rng_options);
+                    let _ = test.generate_value(&mut input);
+                    Token::rng(buffer)
+                }
+            }
+        };
+
+        self.run_tests(test, testfn, tokenfn)
     }
 
     #[cfg(feature = "std")]
-    fn run_with_scope<T, R>(self, test: T, options: driver::Options)
+    fn run_with_scope<T, R>(mut self, test: T, mut options: driver::Options)
     where
         T: FnMut() -> R + core::panic::RefUnwindSafe,
         R: bolero_engine::IntoResult,
     {
-        if options.exhaustive() {
+        options.merge_from(&self.shrink_options);
+
+        // the values of scoped tests are drawn while they run so there's nothing to describe
+        if self.is_describe {
+            return;
+        }
+
+        // the drawn values are the signature of a reduced input without coverage
+        let tyche_on = self.tyche.is_some() || self.reduce.is_some();
+
+        if (options.exhaustive() && !self.is_replay) || self.exhaustive_token().is_some() {
             let testfn = |driver: ExhastiveDriver, test: &mut T| {
-                let (driver, result) = bolero_engine::any::run(driver, test);
-                let result = result.map(|r| {
-                    // For scope tests, we don't have a good way to get a representation
-                    // so we'll use a placeholder
-                    (r, "".to_string())
-                }).map_err(|error| {
-                    (Failure {
-                        seed: None,
-                        error,
-                        input: (),
-                    }
-                    .to_string(), "".to_string())
-                });
+                let (driver, result, representation) = run_scoped(driver, test, tyche_on);
+                let result = result
+                    .map(|r| (r, representation.clone()))
+                    .map_err(|error| Failed {
+                        message: Failure::new(error, (), None)
+                            .with_token(Token::exhaustive(&driver.serialize()).to_string())
+                            .to_string(),
+                        representation,
+                        shrunken: None,
+                    });
                 (driver, result)
             };
 
@@ -329,6 +618,10 @@ impl TestEngine {
         let file_options = &file_options;
         let rng_options = &rng_options;
 
+        let passes = core::mem::take(&mut self.shrink_passes);
+        let crashes = self.crashes.clone();
+        let shrinker = shrink::Context::new(&self.location, rng_options, passes, crashes);
+        let shrinker = &shrinker;
         let mut buffer = vec![];
         let file_driver = bolero_engine::driver::bytes::Driver::new(vec![], file_options);
         let file_driver = bolero_engine::driver::object::Object(file_driver);
@@ -338,53 +631,156 @@ impl TestEngine {
         let testfn = |test: &mut T, data: &input::Test| {
             buffer.clear();
             match data {
-                input::Test::File(file) => {
+                input::Test::Token(token) if token.kind == token::Kind::Rng => {
+                    let rng = input::ReplayRng::new(token.bytes.clone());
+                    let driver = Box::new(Object(driver::Rng::new(rng, rng_options)));
+                    let (_driver, result, representation) = run_scoped(driver, test, tyche_on);
+
+                    result
+                        .map(|r| (r, representation.clone()))
+                        .map_err(|error| {
+                            shrink_scoped(
+                                test,
+                                token.clone(),
+                                None,
+                                error,
+                                representation,
+                                rng_options,
+                                shrinker,
+                            )
+                        })
+                }
+                input::Test::File(_) | input::Test::Token(_) => {
                     let mut driver = file_driver.take().unwrap();
 
                     let mut buf = core::mem::take(&mut buffer);
-                    file.read_into(&mut buf);
+                    match data {
+                        input::Test::File(file) => file.read_into(&mut buf),
+                        input::Test::Token(token) => buf.extend_from_slice(&token.bytes),
+                        input::Test::Rng(_) => unreachable!(),
+                    }
                     driver.reset(buf, file_options);
-                    let (mut driver, result) = bolero_engine::any::run(driver, test);
+                    let (mut driver, result, representation) = run_scoped(driver, test, tyche_on);
                     buffer = driver.reset(vec![], file_options);
                     file_driver = Some(driver);
 
-                    // For scope tests, use a placeholder representation
-                    result.map(|r| (r, "".to_string()))
+                    result
+                        .map(|r| (r, representation.clone()))
                         .map_err(|error| {
-                            (Failure {
-                                seed: None,
+                            let token = Token::bytes(buffer.clone());
+                            shrink_scoped(
+                                test,
+                                token,
+                                None,
                                 error,
-                                input: (), // TODO figure out a better input to show
-                            }
-                            .to_string(), "".to_string())
+                                representation,
+                                file_options,
+                                shrinker,
+                            )
                         })
                 }
                 input::Test::Rng(conf) => {
                     let seed = conf.seed;
                     let driver = conf.driver(rng_options);
                     let driver = Box::new(Object(driver));
-                    let (_driver, result) = bolero_engine::any::run(driver, test);
+                    let (_driver, result, representation) = run_scoped(driver, test, tyche_on);
 
-                    // For scope tests, use a placeholder representation
-                    result.map(|r| (r, "".to_string()))
+                    result
+                        .map(|r| (r, representation.clone()))
                         .map_err(|error| {
-                            (Failure {
-                                seed: Some(seed),
+                            // rerun the test to record the bytes returned by the rng
+                            let driver = Box::new(Object(conf.buffered_driver(rng_options)));
+                            let (driver, _, _) = run_scoped(driver, test, false);
+                            let token = Token::rng(driver.0.as_ref().buffer().to_vec());
+                            shrink_scoped(
+                                test,
+                                token,
+                                Some(seed),
                                 error,
-                                input: (), // TODO figure out a better input to show
-                            }
-                            .to_string(), "".to_string())
+                                representation,
+                                rng_options,
+                                shrinker,
+                            )
                         })
                 }
             }
         };
 
-        self.run_tests(test, testfn)
+        let tokenfn = |test: &mut T, data: &input::Test| match data {
+            input::Test::File(file) => {
+                let mut buffer = vec![];
+                file.read_into(&mut buffer);
+                Token::bytes(buffer)
+            }
+            input::Test::Token(token) => token.clone(),
+            input::Test::Rng(conf) => {
+                // rerun the test to record the bytes returned by the rng
+                let driver = Box::new(Object(conf.buffered_driver(rng_options)));
+                let (driver, _, _) = run_scoped(driver, test, false);
+                Token::rng(driver.0.as_ref().buffer().to_vec())
+            }
+        };
+
+        self.run_tests(test, testfn, tokenfn)
+    }
+
+    /// Generates the replayed input for a parent process that timed out while testing it
+    ///
+    /// The value is printed and the input is saved to the hangs directory, in the same format as
+    /// the crashes directory.
+    fn describe_hang<T>(self, test: T, options: &driver::Options)
+    where
+        T: Test,
+        T::Value: core::fmt::Debug,
+    {
+        let Some(input) = self.replay_tests().next() else {
+            return;
+        };
+
+        let mut buffer = vec![];
+        let (value, token) = match &input.data {
+            input::Test::File(file) => {
+                file.read_into(&mut buffer);
+                let value = test.generate_value(&mut input::Bytes::new(&buffer, options));
+                (value, Token::bytes(buffer))
+            }
+            input::Test::Token(token) => {
+                let value = match token.kind {
+                    token::Kind::Bytes => {
+                        test.generate_value(&mut input::Bytes::new(&token.bytes, options))
+                    }
+                    token::Kind::Rng => {
+                        test.generate_value(&mut input::ReplayRng::input(&token.bytes, options))
+                    }
+                    token::Kind::Exhaustive => return,
+                };
+                (value, token.clone())
+            }
+            input::Test::Rng(conf) => {
+                let value = test.generate_value(&mut conf.buffered_input(&mut buffer, options));
+                (value, Token::rng(buffer))
+            }
+        };
+
+        eprintln!("Input: \n{:#?}\n", value);
+        eprintln!("BOLERO_FAILURE_TOKEN={}\n", token);
+
+        if let Some(path) = self
+            .hangs
+            .as_deref()
+            .and_then(|dir| save_input(dir, &token))
+        {
+            eprintln!("Saved the input to {}", path.display());
+        }
     }
 
-    fn run_tests<S, T>(mut self, mut state: S, mut testfn: T)
+    /// Runs each input with `testfn`
+    ///
+    /// `tokenfn` records the token of an input that's shared with the other workers.
+    fn run_tests<S, T, R>(mut self, mut state: S, mut testfn: T, mut tokenfn: R)
     where
-        T: FnMut(&mut S, &input::Test) -> Result<(bool, String), (String, String)>,
+        T: FnMut(&mut S, &input::Test) -> Result<(bool, String), Failed>,
+        R: FnMut(&mut S, &input::Test) -> Token,
     {
         // if we're fuzzing with cargo-bolero and the iteration count isn't specified
         // then go forever
@@ -392,7 +788,15 @@ impl TestEngine {
             self.rng_cfg.iterations = Some(usize::MAX);
         }
 
-        let tests = self.tests();
+        // the failure token comes first, which is the only input of a shrinking candidate
+        let limit = if self.is_candidate { 1 } else { usize::MAX };
+        let mut tests: Box<dyn Iterator<Item = NamedTest>> = if let Some(reduce) = &self.reduce {
+            Box::new(reduce.tests())
+        } else if self.is_replay {
+            Box::new(self.replay_tests().take(limit))
+        } else {
+            Box::new(self.tests().take(limit))
+        };
 
         let start_time = std::time::Instant::now();
         let test_time = if cfg!(fuzzing_random) {
@@ -406,17 +810,37 @@ impl TestEngine {
             report.spawn_timer();
         }
         let mut outcome = outcome::Outcome::new(&self.location, start_time);
-        let tyche_on = match env::var("BOLERO_TYCHE") {
-            Ok(v) => v,
-            Err(_) => "".to_string(),
-        };
-        outcome.set_jsonpath(tyche_on.clone());
+        outcome.set_jsonpath(self.tyche.clone());
+        for (label, min_percent) in &self.covers {
+            outcome.declare_cover(label, *min_percent);
+        }
+
+        // a hang of a candidate or a description is handled by the parent process
+        let watchdog = self
+            .rng_cfg
+            .timeout
+            .filter(|_| !self.is_candidate && !self.is_describe)
+            .map(|timeout| watchdog::Watchdog::new(timeout, Child::new(&self.location)));
 
         bolero_engine::panic::set_hook();
         bolero_engine::panic::forward_panic(false);
 
-        for input in tests {
-        
+        let mut share = share::Share::new();
+        let mut shared = std::collections::VecDeque::new();
+
+        let mut index = 0;
+        // set once every input was tested, rather than stopping on the time limit
+        let mut is_complete = false;
+        loop {
+            // seeds shared by the other workers are replayed before generating new ones
+            if let Some(share) = share.as_mut() {
+                shared.extend(share.sync());
+            }
+            let Some(input) = shared.pop_front().or_else(|| tests.next()) else {
+                is_complete = true;
+                break;
+            };
+
             if let Some(test_time) = test_time {
                 if start_time.elapsed() > test_time {
                     outcome.on_exit(outcome::ExitReason::MaxDurationExceeded {
@@ -425,53 +849,162 @@ impl TestEngine {
                     });
                     break;
                 }
-
             }
 
             outcome.on_named_test(&input.data);
 
-            match testfn(&mut state, &input.data){ 
-                Ok((is_valid, representation)) => {
-
-
-
+            // observations are always recorded so events and targets can be summarized
+            observe::start();
+            let coverage = if self.tyche.is_some() || share.is_some() || self.reduce.is_some() {
+                coverage::Tracker::start()
+            } else {
+                None
+            };
+            // clear any rejection left over from a previous input
+            let _ = rejection::take();
+            if let Some(watchdog) = watchdog.as_ref() {
+                watchdog.start(&input.data);
+            }
+            let result = testfn(&mut state, &input.data);
+            if let Some(watchdog) = watchdog.as_ref() {
+                watchdog.finish();
+            }
+            let record = observe::finish().unwrap_or_default();
+            let lines = coverage.map(coverage::Tracker::finish).unwrap_or_default();
+            if let Some(share) = share.as_mut() {
+                let is_interesting = match &result {
+                    Ok((is_valid, _)) => *is_valid && share.on_result(&record, &lines),
+                    // a failure ends the worker, so its crash is always new
+                    Err(_) => true,
+                };
+                if is_interesting {
+                    share.report(&tokenfn(&mut state, &input.data));
+                }
+            }
+            // failing inputs are reported instead of failing the reduction
+            if let Some(reduce) = self.reduce.as_ref() {
+                let (representation, failed) = match &result {
+                    Ok((_, representation)) => (representation, false),
+                    Err(failed) => (&failed.representation, true),
+                };
+                reduce.on_result(index, representation, &lines, failed);
+                index += 1;
+                if failed {
+                    continue;
+                }
+            }
+            outcome.set_record(record);
+            outcome.set_coverage(lines);
 
+            match result {
+                Ok((is_valid, representation)) => {
                     report.on_result(is_valid);
+                    outcome.on_validity(rejection_reason(is_valid));
                     outcome.set_representation(representation);
-                    if tyche_on != "" {
-                        let _ = outcome.output_json();
-                    }
-
-
-
+                    outcome.set_how_generated(input.how_generated());
+                    let _ = outcome.output_json();
                 }
-                Err((err, rep)) => {
-
-                    outcome.set_representation(rep);
-
-
-
-                    
+                Err(failed) => {
+                    outcome.set_representation(failed.representation);
+                    outcome.set_how_generated(input.how_generated());
                     bolero_engine::panic::forward_panic(true);
                     outcome.on_exit(outcome::ExitReason::TestFailure);
-                    if tyche_on != "" {
+                    let _ = outcome.output_json();
+
+                    // the shrinking runs aren't observed so only the final input is reported
+                    if let Some(shrunken) = failed.shrunken {
+                        outcome.set_representation(shrunken);
+                        outcome.set_how_generated(input.how_shrunk());
+                        outcome.set_record(Default::default());
+                        outcome.set_coverage(Default::default());
                         let _ = outcome.output_json();
                     }
-                    eprintln!("{}", err);
+
+                    eprintln!("{}", failed.message);
                     panic!("test failed");
                 }
             }
         }
+
+        report.flush();
+
+        // a candidate or a replay only reports whether its inputs failed
+        if self.is_candidate || self.is_replay || self.reduce.is_some() {
+            return;
+        }
+
+        if is_complete {
+            outcome.on_exit(outcome::ExitReason::MaxIterationsReached {
+                limit: self.rng_cfg.iterations_or_default(),
+            });
+        }
+
+        if let Err(message) = outcome.check_covers() {
+            eprintln!("{message}");
+            panic!("test failed");
+        }
     }
 
     fn run_exhaustive<S, F>(self, mut state: S, mut testfn: F, options: driver::Options)
     where
-        F: FnMut(ExhastiveDriver, &mut S) -> (ExhastiveDriver, Result<(bool, String), (String, String)>),
+        F: FnMut(ExhastiveDriver, &mut S) -> (ExhastiveDriver, Result<(bool, String), Failed>),
     {
         bolero_engine::panic::set_hook();
         bolero_engine::panic::forward_panic(false);
 
-        let driver = exhaustive::Driver::new(&options);
+        let mut driver = exhaustive::Driver::new(&options);
+
+        // a failure token replays a single case, while a resumed enumeration continues past it
+        let replay = self.exhaustive_token();
+
+        // the replayed case is run by every worker
+        if let Some((index, count)) = self.shard.filter(|_| replay.is_none()) {
+            driver.set_shard(index, count);
+        }
+
+        // checkpoints are only used when enumerating from the start
+        let checkpoint = self
+            .checkpoints
+            .clone()
+            .filter(|_| replay.is_none() && self.resume.is_none())
+            .map(|dir| checkpoint::Checkpoint::new(dir, self.shard, &options));
+
+        if let Some(checkpoint) = checkpoint.as_ref() {
+            if let Some(progress) = checkpoint.completed() {
+                eprintln!(
+                    "The exhaustive enumeration of {} inputs was already completed. Remove {} to run it again.",
+                    progress.inputs,
+                    checkpoint.marker().display(),
+                );
+                return;
+            }
+        }
+
+        let resumed = checkpoint.as_ref().and_then(|checkpoint| checkpoint.load());
+
+        let how_generated = if let Some(frames) = replay.as_ref() {
+            driver.resume(frames);
+            "replayed from BOLERO_FAILURE_TOKEN"
+        } else if let Some(frames) = self.resume.as_ref() {
+            driver.resume(frames);
+            "generated by exhaustive enumeration resumed from BOLERO_EXHAUSTIVE_RESUME"
+        } else if let Some(progress) = resumed.as_ref() {
+            eprintln!(
+                "Resuming the exhaustive enumeration from a checkpoint after {} inputs",
+                progress.inputs
+            );
+            match progress.tier {
+                Some(tier) => driver.resume_tier(tier, &progress.pending),
+                None => driver.resume(&progress.state),
+            }
+            if let Some((depth, prefix)) = progress.shard {
+                driver.set_shard_position(depth, prefix);
+            }
+            "generated by exhaustive enumeration resumed from a checkpoint"
+        } else {
+            "generated by exhaustive enumeration"
+        };
+
         let mut driver = Box::new(Object(driver));
         let test_time = self.rng_cfg.test_time;
         let start_time = std::time::Instant::now();
@@ -479,34 +1012,185 @@ impl TestEngine {
         let mut report = report::Report::default();
         // when running exhaustive tests, it's nice to have the progress displayed
         report.spawn_timer();
-        let _outcome = outcome::Outcome::new(&self.location, start_time);
+        let mut outcome = outcome::Outcome::new(&self.location, start_time);
+        outcome.set_jsonpath(self.tyche.clone());
+        for (label, min_percent) in &self.covers {
+            outcome.declare_cover(label, *min_percent);
+        }
 
+        // the progress includes the runs that came before the checkpoint
+        let resumed = resumed.unwrap_or_default();
+        // the checkpointed input is tested again to discover the bounds of its frames
+        let mut inputs = resumed.inputs.saturating_sub(1);
+        let progress = |driver: &exhaustive::Driver, inputs| checkpoint::Progress {
+            state: driver.serialize(),
+            tier: driver.tier(),
+            pending: driver.pending(),
+            shard: driver.shard_position(),
+            inputs,
+            elapsed: resumed.elapsed + start_time.elapsed(),
+        };
+        let mut last_checkpoint = start_time;
+        let mut is_exhausted = true;
 
         while driver.step().is_continue() {
             if let Some(test_time) = test_time {
                 if start_time.elapsed() > test_time {
+                    outcome.on_exit(outcome::ExitReason::MaxDurationExceeded {
+                        limit: test_time,
+                        default: false,
+                    });
+                    is_exhausted = false;
                     break;
                 }
             }
 
+            // observations are always recorded so events and targets can be summarized
+            observe::start();
+            let coverage = if self.tyche.is_some() {
+                coverage::Tracker::start()
+            } else {
+                None
+            };
+            let _ = rejection::take();
             let (drvr, result) = testfn(driver, &mut state);
             driver = drvr;
+            let record = observe::finish().unwrap_or_default();
+            let coverage = coverage.map(coverage::Tracker::finish).unwrap_or_default();
+
+            // the state outgrew its size tier before it was tested, and is tested in a later one
+            if driver.is_deferred() {
+                continue;
+            }
+
+            outcome.on_exhaustive_input();
+            inputs += 1;
+            outcome.set_record(record);
+            outcome.set_coverage(coverage);
+            outcome.set_how_generated(how_generated.to_string());
 
             match result {
-                Ok((is_valid, _representation)) => {
+                Ok((is_valid, representation)) => {
                     report.on_estimate(driver.estimate());
-                    report.on_result(is_valid);
+                    // the states shared with other shards are only counted by their owner
+                    if driver.is_owned() {
+                        report.on_result(is_valid);
+                    }
+                    outcome.on_validity(rejection_reason(is_valid));
+                    outcome.set_representation(representation);
+                    let _ = outcome.output_json();
+
+                    if let Some(checkpoint) = checkpoint.as_ref() {
+                        if last_checkpoint.elapsed() >= checkpoint::INTERVAL {
+                            let _ = checkpoint.save(&progress(&driver, inputs));
+                            last_checkpoint = std::time::Instant::now();
+                        }
+                    }
                 }
-                Err((error, rep)) => {
+                Err(failed) => {
+                    let resume = Token::exhaustive(&driver.serialize());
+                    outcome.set_representation(failed.representation.clone());
+                    outcome.on_exit(outcome::ExitReason::TestFailure);
+                    let _ = outcome.output_json();
+
+                    let shrunken = suspend_observations(|| {
+                        shrink_exhaustive(
+                            driver,
+                            &mut state,
+                            &mut testfn,
+                            options.shrink_time_or_default(),
+                        )
+                    });
+
+                    // the shrinking runs aren't observed so only the final input is reported
+                    let failed = if let Some(shrunken) = shrunken {
+                        outcome.set_representation(shrunken.representation.clone());
+                        outcome.set_how_generated("shrunk from exhaustive enumeration".to_string());
+                        outcome.set_record(Default::default());
+                        outcome.set_coverage(Default::default());
+                        let _ = outcome.output_json();
+                        shrunken
+                    } else {
+                        failed
+                    };
+
                     bolero_engine::panic::forward_panic(true);
-                    eprintln!("{error}");
+                    eprintln!("{}", failed.message);
+                    eprintln!("To continue the enumeration from the failing case, set:");
+                    eprintln!("BOLERO_EXHAUSTIVE_RESUME={resume}\n");
                     panic!("test failed");
                 }
             }
+
+            // only the replayed case is run, so the covers aren't checked
+            if replay.is_some() {
+                return;
+            }
+        }
+
+        outcome.on_complete_tier(driver.complete_tier());
+        report.flush();
+
+        if let Some(checkpoint) = checkpoint.as_ref() {
+            let progress = progress(&driver, inputs);
+            let _ = if is_exhausted {
+                checkpoint.complete(&progress)
+            } else {
+                checkpoint.save(&progress)
+            };
+        }
+
+        if is_exhausted {
+            outcome.on_exit(outcome::ExitReason::Exhausted);
+        }
+
+        // shards and resumed enumerations only test some of the inputs, which skews the covers
+        if self.shard.is_some() || self.resume.is_some() || resumed.inputs > 0 {
+            return;
+        }
+
+        if let Err(message) = outcome.check_covers() {
+            eprintln!("{message}");
+            panic!("test failed");
         }
     }
 }
 
+/// Shrinks the failing case that `driver` is positioned on
+///
+/// Returns the failure of the smallest failing frame state, if it differs from the original case.
+/// The failure's token holds the shrunken state.
+fn shrink_exhaustive<S, F>(
+    driver: ExhastiveDriver,
+    state: &mut S,
+    testfn: &mut F,
+    shrink_time: Duration,
+) -> Option<Failed>
+where
+    F: FnMut(ExhastiveDriver, &mut S) -> (ExhastiveDriver, Result<(bool, String), Failed>),
+{
+    let initial = driver.serialize();
+    let mut driver = Some(driver);
+    let mut execute = |frames: &[u64]| {
+        let mut d = driver.take().unwrap();
+        d.deserialize(frames);
+        let (d, result) = testfn(d, state);
+        driver = Some(d);
+        result.err()
+    };
+
+    let frames = frames::shrink(initial.clone(), shrink_time, |frames| {
+        execute(frames).is_some()
+    });
+
+    if frames == initial {
+        return None;
+    }
+
+    // rerun the smallest case to report its failure
+    execute(&frames)
+}
+
 impl<T> Engine<T> for TestEngine
 where
     T: Test,
@@ -534,6 +1218,229 @@ impl bolero_engine::ScopedEngine for TestEngine {
     }
 }
 
+/// Runs a scoped test with the provided driver
+///
+/// If `record` is set, the values drawn with `bolero::any` are returned as the
+/// test case representation, one per line.
+#[cfg(feature = "std")]
+fn run_scoped<D, T, R>(
+    driver: Box<D>,
+    test: &mut T,
+    record: bool,
+) -> (
+    Box<D>,
+    Result<bool, bolero_engine::panic::PanicError>,
+    String,
+)
+where
+    D: 'static + driver::object::DynDriver + core::any::Any,
+    T: FnMut() -> R + core::panic::RefUnwindSafe,
+    R: bolero_engine::IntoResult,
+{
+    if !record {
+        let (driver, result) = bolero_engine::any::run(driver, test);
+        return (driver, result, String::new());
+    }
+
+    let ((driver, result), draws) =
+        bolero_engine::any::scope::record(|| bolero_engine::any::run(driver, test));
+
+    let mut representation = String::new();
+    for draw in draws {
+        if !representation.is_empty() {
+            representation.push('\n');
+        }
+        representation.push_str(&draw.to_string());
+    }
+
+    (driver, result, representation)
+}
+
+/// Shrinks a failing scoped test by rerunning it over the bytes recorded in `token`
+///
+/// The report lists each value drawn with `bolero::any` in the smallest failing run.
+#[cfg(feature = "std")]
+fn shrink_scoped<T, R>(
+    test: &mut T,
+    token: Token,
+    seed: Option<Seed>,
+    error: bolero_engine::panic::PanicError,
+    representation: String,
+    options: &driver::Options,
+    shrinker: &shrink::Context<'_>,
+) -> Failed
+where
+    T: FnMut() -> R + core::panic::RefUnwindSafe,
+    R: bolero_engine::IntoResult,
+{
+    let mut test = scoped::ScopedTest::new(test, token.kind, options);
+    let input = test.generate_value(&mut input::Bytes::new(&token.bytes, options));
+
+    // keep the smallest input on disk in case shrinking is interrupted
+    let mut best = shrinker.best(token.kind);
+    let mut on_best = |input: &[u8]| best.save(input);
+    let hooks = shrinker.hooks(token.kind, Some(&mut on_best));
+
+    // the shrinker leaves the shrunken bytes in the buffer
+    let mut buffer = token.bytes.clone();
+    let shrunken = suspend_observations(|| match token.kind {
+        token::Kind::Bytes => {
+            bolero_engine::shrink::shrink_with(&mut test, &mut buffer, seed, options, hooks)
+        }
+        token::Kind::Rng => {
+            let input = input::RngReplayInput {
+                buffer: &mut buffer,
+            };
+            bolero_engine::shrink::shrink_with(&mut test, input, seed, options, hooks)
+        }
+        token::Kind::Exhaustive => unreachable!("shrunk by the exhaustive driver"),
+    });
+
+    if let Some(mut shrunken) = shrunken {
+        let saved = best
+            .finish(&buffer)
+            .or_else(|| shrinker.save_artifact(&buffer, &shrunken));
+        let token = Token {
+            kind: token.kind,
+            bytes: buffer,
+        };
+        shrunken.token = Some(token.to_string());
+        let mut message = format!("{:#}", shrunken);
+        if let Some(path) = saved {
+            message.push_str(&format!(
+                "\nSaved the shrunken input to {}\n",
+                path.display()
+            ));
+        }
+        Failed {
+            message,
+            representation,
+            shrunken: Some(format!("{:?}", shrunken.input)),
+        }
+    } else {
+        Failed {
+            message: format!(
+                "{:#}",
+                Failure::new(error, input, seed).with_token(token.to_string())
+            ),
+            representation,
+            shrunken: None,
+        }
+    }
+}
+
+/// Tests an input that's read directly by the generators, like a corpus file
+fn test_bytes<T>(
+    test: &mut T,
+    bytes: &[u8],
+    options: &driver::Options,
+    shrinker: &shrink::Context<'_>,
+) -> Result<(bool, String), Failed>
+where
+    T: Test,
+    T::Value: core::fmt::Debug,
+{
+    let mut input = input::Bytes::new(bytes, options);
+    let result = test.test(&mut input);
+
+    // Generate a value for representation after the test, unless it was rejected
+    let representation = if matches!(result, Ok(false)) {
+        String::new()
+    } else {
+        let mut repr_input = input::Bytes::new(bytes, options);
+        let value = test.generate_value(&mut repr_input);
+        format!("{:?}", value)
+    };
+
+    let error = match result {
+        Ok(is_valid) => return Ok((is_valid, representation)),
+        Err(error) => error,
+    };
+
+    // keep the smallest input on disk in case shrinking is interrupted
+    let mut best = shrinker.best(token::Kind::Bytes);
+    let mut on_best = |input: &[u8]| best.save(input);
+    let hooks = shrinker.hooks(token::Kind::Bytes, Some(&mut on_best));
+
+    // the shrinker leaves the shrunken bytes in the buffer
+    let mut buffer = bytes.to_vec();
+    let shrunken = suspend_observations(|| {
+        bolero_engine::shrink::shrink_with(test, &mut buffer, None, options, hooks)
+    });
+
+    if let Some(mut shrunken) = shrunken {
+        let saved = best
+            .finish(&buffer)
+            .or_else(|| shrinker.save_artifact(&buffer, &shrunken));
+        shrunken.token = Some(Token::bytes(buffer).to_string());
+        let mut message = format!("{:#}", shrunken);
+        if let Some(path) = saved {
+            message.push_str(&format!(
+                "\nSaved the shrunken input to {}\n",
+                path.display()
+            ));
+        }
+        Err(Failed {
+            message,
+            representation,
+            shrunken: Some(format!("{:?}", shrunken.input)),
+        })
+    } else {
+        Err(Failed {
+            message: format!(
+                "{:#}",
+                Failure::new(error, bytes.to_vec(), None)
+                    .with_token(Token::bytes(bytes.to_vec()).to_string())
+            ),
+            representation,
+            shrunken: None,
+        })
+    }
+}
+
+/// Tests an input by replaying rng bytes recorded from a previous run
+///
+/// The bytes are only meaningful to the rng driver so the failure isn't shrunk.
+fn test_replayed_rng<T>(
+    test: &mut T,
+    bytes: &[u8],
+    options: &driver::Options,
+) -> Result<(bool, String), Failed>
+where
+    T: Test,
+    T::Value: core::fmt::Debug,
+{
+    let mut input = input::ReplayRng::input(bytes, options);
+    let result = test.test(&mut input);
+
+    let value = if matches!(result, Ok(false)) {
+        None
+    } else {
+        let mut repr_input = input::ReplayRng::input(bytes, options);
+        Some(test.generate_value(&mut repr_input))
+    };
+    let representation = value
+        .as_ref()
+        .map_or_else(String::new, |value| format!("{:?}", value));
+
+    result
+        .map(|is_valid| (is_valid, representation.clone()))
+        .map_err(|error| Failed {
+            message: format!(
+                "{:#}",
+                Failure::new(error, value, None).with_token(Token::rng(bytes.to_vec()).to_string())
+            ),
+            representation,
+            shrunken: None,
+        })
+}
+
+/// Executes `f` without attributing any of its observations, coverage or time to the current test
+/// case
+fn suspend_observations<F: FnOnce() -> R, R>(f: F) -> R {
+    watchdog::suspend(|| observe::suspend(|| coverage::suspend(f)))
+}
+
 fn progress() {
     if cfg!(miri) {
         use std::io::{stderr, Write};
@@ -542,4 +1449,169 @@ fn progress() {
         #[allow(clippy::explicit_write)]
         let _ = write!(stderr(), ".");
     }
-}
\ No newline at end of file
+}
+
+/// Describes why an input was rejected, or `None` if it was valid
+fn rejection_reason(is_valid: bool) -> Option<String> {
+    if is_valid {
+        return None;
+    }
+    let reason = rejection::take()
+        .map(|rejection| rejection.to_string())
+        .unwrap_or_else(|| "the generator could not produce a value".to_string());
+    Some(reason)
+}
+
+/// Returns the index and count of the `cargo bolero test --jobs` worker
+fn worker_shard() -> Option<(usize, usize)> {
+    let var = |name| std::env::var(name).ok()?.parse::<usize>().ok();
+    let index = var("BOLERO_RANDOM_WORKER")?;
+    let count = var("BOLERO_RANDOM_WORKERS")?;
+    (index < count).then_some((index, count))
+}
+
+/// Returns the directory where the progress of exhaustive runs should be saved
+///
+/// Checkpoints are enabled by default for `cargo bolero test`, and can be toggled by setting
+/// `BOLERO_EXHAUSTIVE_CHECKPOINT` to `0` or `1`.
+fn checkpoints_dir(location: &TargetLocation) -> Option<PathBuf> {
+    // the crate's own tests shouldn't write into the source tree
+    if cfg!(test) || cfg!(miri) {
+        return None;
+    }
+
+    let is_enabled = match std::env::var("BOLERO_EXHAUSTIVE_CHECKPOINT").as_deref() {
+        Ok("0" | "false") => false,
+        Ok("1" | "true") => true,
+        _ => cfg!(fuzzing_random),
+    };
+
+    if !is_enabled {
+        return None;
+    }
+
+    Some(location.work_dir()?.join("exhaustive"))
+}
+
+/// Returns the directory where shrunken rng failures should be saved
+///
+/// Saving can be disabled by setting `BOLERO_SAVE_CRASHES=0`.
+fn crashes_dir(location: &TargetLocation) -> Option<PathBuf> {
+    // the crate's own failing tests shouldn't write into the source tree
+    if cfg!(test) || cfg!(miri) {
+        return None;
+    }
+
+    if matches!(
+        std::env::var("BOLERO_SAVE_CRASHES").as_deref(),
+        Ok("0" | "false")
+    ) {
+        return None;
+    }
+
+    let mut dir = location.work_dir()?;
+    dir.extend(input::Origin::Crashes.dirs());
+    Some(dir)
+}
+
+/// Returns the directory where inputs that exceed the timeout are saved
+///
+/// The directory is next to the crashes directory, and hangs are saved even if
+/// `BOLERO_SAVE_CRASHES=0` is set.
+fn hangs_dir(location: &TargetLocation) -> Option<PathBuf> {
+    // the crate's own tests shouldn't write into the source tree
+    if cfg!(test) || cfg!(miri) {
+        return None;
+    }
+
+    Some(location.work_dir()?.join("hangs"))
+}
+
+/// Runs the target in a child process of the test binary
+#[derive(Clone, Debug)]
+struct Child {
+    exe: PathBuf,
+    args: Vec<String>,
+}
+
+impl Child {
+    fn new(location: &TargetLocation) -> Option<Self> {
+        if cfg!(miri) {
+            return None;
+        }
+
+        let exe = std::env::current_exe().ok()?;
+
+        // run the same libtest test as the parent
+        let args = if location.is_harnessed() {
+            vec![
+                location.item_path(),
+                "--exact".to_string(),
+                "--nocapture".to_string(),
+                "--test-threads".to_string(),
+                "1".to_string(),
+            ]
+        } else {
+            vec![]
+        };
+
+        Some(Self { exe, args })
+    }
+
+    /// Returns a command for the target, without the inputs or the worker of the parent
+    fn command(&self) -> Command {
+        let mut cmd = Command::new(&self.exe);
+        cmd.args(&self.args)
+            .env_remove("BOLERO_FAILURE_TOKEN")
+            .env_remove("BOLERO_RANDOM_WORKER")
+            .env_remove("BOLERO_RANDOM_WORKERS")
+            .env_remove("BOLERO_TYCHE")
+            .env_remove("BOLERO_TMIN_OUTPUT")
+            .env_remove("CARGO_BOLERO_SELECT")
+            .stdin(Stdio::null());
+        cmd
+    }
+}
+
+/// Writes `input` to the crashes directory, named after a hash of its contents
+///
+/// Returns the path of the file, or `None` if it couldn't be written.
+fn save_crash(dir: &std::path::Path, input: &[u8]) -> Option<PathBuf> {
+    let path = crash_path(dir, input);
+
+    std::fs::create_dir_all(dir).ok()?;
+    std::fs::write(&path, input).ok()?;
+    Some(path)
+}
+
+/// Returns the path that `input` is saved to in the crashes directory
+fn crash_path(dir: &std::path::Path, input: &[u8]) -> PathBuf {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    input.hash(&mut hasher);
+    dir.join(format!("{:016x}", hasher.finish()))
+}
+
+/// Writes the input of `token` to `dir` so it can be replayed like a crash file
+///
+/// Rng inputs are only replayed exactly by the rng driver, so they're saved as a `.token` file.
+fn save_input(dir: &std::path::Path, token: &Token) -> Option<PathBuf> {
+    if token.kind != token::Kind::Rng {
+        return save_crash(dir, &token.bytes);
+    }
+
+    let path = input_path(dir, token);
+    std::fs::create_dir_all(dir).ok()?;
+    std::fs::write(&path, token.to_string()).ok()?;
+    Some(path)
+}
+
+/// Returns the path that the input of `token` is saved to by [`save_input`]
+fn input_path(dir: &std::path::Path, token: &Token) -> PathBuf {
+    let path = crash_path(dir, &token.bytes);
+    match token.kind {
+        token::Kind::Rng => path.with_extension("token"),
+        _ => path,
+    }
+}
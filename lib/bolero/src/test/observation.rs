@@ -0,0 +1,277 @@
+use super::coverage::Lines;
+use crate::observe::Payload;
+use core::fmt;
+use std::collections::BTreeMap;
+
+/// A single Tyche test case observation
+///
+/// Each observation is serialized as one line of a JSON Lines file. See
+/// <https://hypothesis.readthedocs.io/en/latest/reference/integrations.html#test-case>
+/// for the schema.
+pub struct Observation<'a> {
+    pub run_start: u64,
+    pub property: &'a str,
+    pub status: Status,
+    pub status_reason: &'a str,
+    pub representation: &'a str,
+    pub arguments: &'a BTreeMap<String, String>,
+    pub how_generated: &'a str,
+    pub features: &'a BTreeMap<String, Payload>,
+    pub targets: &'a BTreeMap<String, f64>,
+    pub coverage: &'a Lines,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Passed,
+    Failed,
+    GaveUp,
+}
+
+impl fmt::Display for Observation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut object = Object::new(f)?;
+        object.field("type", "test_case")?;
+        object.field("run_start", &self.run_start)?;
+        object.field("property", self.property)?;
+        object.field("status", &self.status)?;
+        object.field("status_reason", self.status_reason)?;
+        object.field("representation", self.representation)?;
+        object.field("arguments", self.arguments)?;
+        object.field("how_generated", self.how_generated)?;
+        object.field(
+            "features",
+            &Features {
+                features: self.features,
+                targets: self.targets,
+            },
+        )?;
+        object.field("metadata", &Metadata)?;
+        object.field("coverage", self.coverage)?;
+        object.finish()
+    }
+}
+
+/// Events and targets share the `features` object, with targets prefixed by `target:`
+struct Features<'a> {
+    features: &'a BTreeMap<String, Payload>,
+    targets: &'a BTreeMap<String, f64>,
+}
+
+impl Json for Features<'_> {
+    fn write_json(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut object = Object::new(f)?;
+        for (key, value) in self.features {
+            object.field(key, value)?;
+        }
+        for (label, score) in self.targets {
+            object.field(&format!("target:{label}"), score)?;
+        }
+        object.finish()
+    }
+}
+
+struct Metadata;
+
+impl Json for Metadata {
+    fn write_json(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut object = Object::new(f)?;
+        object.field("traceback", &None::<&str>)?;
+        object.finish()
+    }
+}
+
+/// Writes a value as JSON
+trait Json {
+    fn write_json(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+impl Json for str {
+    fn write_json(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("\"")?;
+        for c in self.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if c.is_control() => {
+                    // escape all control characters, including the ones JSON doesn't require
+                    let mut buf = [0; 2];
+                    for unit in c.encode_utf16(&mut buf) {
+                        write!(f, "\\u{unit:04x}")?;
+                    }
+                }
+                c => write!(f, "{c}")?,
+            }
+        }
+        f.write_str("\"")
+    }
+}
+
+impl Json for String {
+    fn write_json(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().write_json(f)
+    }
+}
+
+impl<T: Json + ?Sized> Json for &T {
+    fn write_json(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).write_json(f)
+    }
+}
+
+impl<T: Json> Json for Option<T> {
+    fn write_json(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Some(value) => value.write_json(f),
+            None => f.write_str("null"),
+        }
+    }
+}
+
+macro_rules! impl_integer {
+    ($($ty:ty),*) => {
+        $(
+            impl Json for $ty {
+                fn write_json(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    write!(f, "{self}")
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(u32, u64, i128);
+
+impl Json for f64 {
+    fn write_json(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // JSON doesn't support NaN or infinity so encode them as strings
+        if self.is_finite() {
+            write!(f, "{self:?}")
+        } else {
+            self.to_string().write_json(f)
+        }
+    }
+}
+
+impl Json for Payload {
+    fn write_json(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Payload::Integer(value) => value.write_json(f),
+            Payload::Float(value) => value.write_json(f),
+            Payload::String(value) => value.write_json(f),
+        }
+    }
+}
+
+impl Json for Status {
+    fn write_json(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Passed => "passed",
+            Status::Failed => "failed",
+            Status::GaveUp => "gave_up",
+        }
+        .write_json(f)
+    }
+}
+
+impl<T: Json> Json for [T] {
+    fn write_json(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[")?;
+        for (idx, value) in self.iter().enumerate() {
+            if idx > 0 {
+                f.write_str(",")?;
+            }
+            value.write_json(f)?;
+        }
+        f.write_str("]")
+    }
+}
+
+impl<T: Json> Json for Vec<T> {
+    fn write_json(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_slice().write_json(f)
+    }
+}
+
+impl<T: Json> Json for BTreeMap<String, T> {
+    fn write_json(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut object = Object::new(f)?;
+        for (key, value) in self {
+            object.field(key, value)?;
+        }
+        object.finish()
+    }
+}
+
+struct Object<'a, 'b> {
+    f: &'a mut fmt::Formatter<'b>,
+    is_empty: bool,
+}
+
+impl<'a, 'b> Object<'a, 'b> {
+    fn new(f: &'a mut fmt::Formatter<'b>) -> Result<Self, fmt::Error> {
+        f.write_str("{")?;
+        Ok(Self { f, is_empty: true })
+    }
+
+    fn field<T: Json + ?Sized>(&mut self, key: &str, value: &T) -> fmt::Result {
+        if !core::mem::take(&mut self.is_empty) {
+            self.f.write_str(",")?;
+        }
+        key.write_json(self.f)?;
+        self.f.write_str(":")?;
+        value.write_json(self.f)
+    }
+
+    fn finish(self) -> fmt::Result {
+        self.f.write_str("}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Value<T>(T);
+
+    impl<T: Json> fmt::Display for Value<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.0.write_json(f)
+        }
+    }
+
+    #[test]
+    fn string_escape_test() {
+        for value in [
+            "",
+            "plain",
+            "\"quoted\"",
+            "back\\slash",
+            "new\nline\r\n",
+            "\t\u{0}\u{1f}\u{7f}\u{85}",
+            "unicode: é ☃ 🦀 \u{2028}\u{2029}",
+        ] {
+            let encoded = Value(value).to_string();
+            let decoded: String = serde_json::from_str(&encoded).unwrap();
+            assert_eq!(decoded, value, "{encoded}");
+        }
+    }
+
+    #[test]
+    fn float_test() {
+        for (value, expected) in [
+            (1.0, serde_json::json!(1.0)),
+            (-0.5, serde_json::json!(-0.5)),
+            (1e300, serde_json::json!(1e300)),
+            (f64::NAN, serde_json::json!("NaN")),
+            (f64::INFINITY, serde_json::json!("inf")),
+        ] {
+            let encoded = Value(value).to_string();
+            let decoded: serde_json::Value = serde_json::from_str(&encoded).unwrap();
+            assert_eq!(decoded, expected, "{encoded}");
+        }
+    }
+}
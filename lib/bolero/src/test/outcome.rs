@@ -1,14 +1,29 @@
+use super::{
+    coverage::Lines,
+    observation::{Observation, Status},
+    statistics::Statistics,
+};
+use crate::observe::Record;
 use bolero_engine::TargetLocation;
 use core::{fmt, time::Duration};
+use std::io::Write;
+use std::path::PathBuf;
 use std::time::Instant;
 use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
-use std::collections::HashMap;
-use std::io::Write;
 
 pub enum ExitReason {
-    MaxDurationExceeded { limit: Duration, default: bool },
+    MaxDurationExceeded {
+        limit: Duration,
+        default: bool,
+    },
+    MaxIterationsReached {
+        limit: usize,
+    },
+    /// Every input of an exhaustive enumeration was tested
+    Exhausted,
     TestFailure,
+    CoverageNotMet,
 }
 
 impl fmt::Display for ExitReason {
@@ -22,7 +37,12 @@ impl fmt::Display for ExitReason {
                     if *default { " - default" } else { "" }
                 )
             }
+            ExitReason::MaxIterationsReached { limit } => {
+                write!(f, "max iterations ({limit}) reached")
+            }
+            ExitReason::Exhausted => write!(f, "search space exhausted"),
             ExitReason::TestFailure => write!(f, "test failure"),
+            ExitReason::CoverageNotMet => write!(f, "coverage requirements not met"),
         }
     }
 }
@@ -33,13 +53,18 @@ pub struct Outcome<'a> {
     corpus_input: u64,
     rng_input: u64,
     exhaustive_input: u64,
+    /// The largest size tier that the exhaustive driver fully enumerated
+    complete_tier: Option<u64>,
     total: u64,
+    rejected: u64,
+    rejection: Option<String>,
     exit_reason: Option<ExitReason>,
-    features: String,
-    arguments: String,
-    coverage: String,
+    record: Record,
+    statistics: Statistics,
+    coverage: Lines,
     representation: String,
-    json_path: String,
+    how_generated: String,
+    json_path: Option<PathBuf>,
     json_time: std::time::Duration,
 }
 
@@ -73,6 +98,15 @@ impl fmt::Display for Outcome<'_> {
             }
         }
 
+        if let Some(tier) = self.complete_tier {
+            write!(f, " | complete size tier: {tier}")?;
+        }
+
+        if self.rejected > 0 {
+            let rate = self.rejected as f64 / self.total as f64 * 100.0;
+            write!(f, " | rejected inputs: {} ({rate:0.1}%)", self.rejected)?;
+        }
+
         if let Some(reason) = &self.exit_reason {
             write!(f, " | exit reason: {}", reason)?;
         }
@@ -89,25 +123,27 @@ impl<'a> Outcome<'a> {
             corpus_input: 0,
             rng_input: 0,
             exhaustive_input: 0,
+            complete_tier: None,
             total: 0,
+            rejected: 0,
+            rejection: None,
             representation: String::from("{}"),
+            how_generated: String::new(),
             exit_reason: None,
-            features: String::from("{}"),
-            arguments: String::from("{}"),
-            coverage: String::from("{}"),
-            json_path: String::new(),
+            record: Record::default(),
+            statistics: Statistics::default(),
+            coverage: Lines::new(),
+            json_path: None,
             json_time: SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .expect("Time went backwards"),
-            
         }
     }
 
-
     pub fn on_named_test(&mut self, test: &super::input::Test) {
         match test {
             super::input::Test::Rng(_) => self.on_rng_input(),
-            super::input::Test::File(_) => self.on_corpus_input(),
+            super::input::Test::File(_) | super::input::Test::Token(_) => self.on_corpus_input(),
         }
     }
 
@@ -128,65 +164,117 @@ impl<'a> Outcome<'a> {
         self.total += 1;
     }
 
+    pub fn on_complete_tier(&mut self, tier: Option<u64>) {
+        self.complete_tier = tier;
+    }
+
+    /// Records whether the current input was valid, along with the reason it was rejected
+    pub fn on_validity(&mut self, rejection: Option<String>) {
+        if rejection.is_some() {
+            self.rejected += 1;
+        } else {
+            self.statistics.on_valid(&self.record);
+        }
+        self.rejection = rejection;
+    }
+
     pub fn on_exit(&mut self, reason: ExitReason) {
         self.exit_reason = Some(reason);
     }
+    /// Adds a coverage requirement that's checked even if its `cover!` site is never reached
+    pub fn declare_cover(&mut self, label: &str, min_percent: f64) {
+        self.statistics.declare_cover(label, min_percent);
+    }
+
+    /// Fails the run if any of the `cover!` requirements were under-sampled
+    ///
+    /// The requirements are only checked once the run reaches its time or iteration limit, or
+    /// exhausts the inputs, since a run that stops early hasn't sampled enough test cases.
+    pub fn check_covers(&mut self) -> Result<(), String> {
+        if !matches!(
+            self.exit_reason,
+            Some(
+                ExitReason::MaxDurationExceeded { .. }
+                    | ExitReason::MaxIterationsReached { .. }
+                    | ExitReason::Exhausted
+            )
+        ) {
+            return Ok(());
+        }
+
+        let unmet = self.statistics.unmet_covers();
+        if unmet.is_empty() {
+            return Ok(());
+        }
+        self.on_exit(ExitReason::CoverageNotMet);
+        Err(unmet.join("\n"))
+    }
+
     pub fn set_representation(&mut self, representation: String) {
         self.representation = representation;
     }
-    pub fn set_jsonpath(&mut self, json_path: String) {
+    pub fn set_how_generated(&mut self, how_generated: String) {
+        self.how_generated = how_generated;
+    }
+    pub fn set_record(&mut self, record: Record) {
+        self.statistics.on_record(&record);
+        self.record = record;
+    }
+    pub fn set_coverage(&mut self, coverage: Lines) {
+        self.coverage = coverage;
+    }
+    pub fn set_jsonpath(&mut self, json_path: Option<PathBuf>) {
         self.json_path = json_path;
     }
-    pub fn output_json(&self) -> std::io::Result<()>{
-        let status = match &self.exit_reason {
-            Some(ExitReason::TestFailure) => "failed",
-            _ => "passed",
+    pub fn output_json(&self) -> std::io::Result<()> {
+        let Some(file_name) = self.json_path.as_ref() else {
+            return Ok(());
         };
 
-        let status_reason = match &self.exit_reason {
-            Some(reason) => reason.to_string(),
-            None => String::new(),
+        let (status, status_reason) = match (&self.exit_reason, &self.rejection) {
+            (Some(reason @ ExitReason::TestFailure), _) => (Status::Failed, reason.to_string()),
+            (_, Some(rejection)) => (Status::GaveUp, rejection.clone()),
+            (Some(reason), None) => (Status::Passed, reason.to_string()),
+            (None, None) => (Status::Passed, String::new()),
+        };
+
+        let property = self
+            .location
+            .test_name
+            .clone()
+            .unwrap_or_else(|| self.location.item_path());
+
+        let observation = Observation {
+            run_start: self.json_time.as_secs(),
+            property: &property,
+            status,
+            status_reason: &status_reason,
+            representation: &self.representation,
+            arguments: &self.record.arguments,
+            how_generated: &self.how_generated,
+            features: &self.record.features,
+            targets: &self.record.targets,
+            coverage: &self.coverage,
         };
 
-        let property = self.location.test_name.as_ref()
-            .map(|s| s.to_string())
-            .unwrap_or_else(|| self.location.item_path.to_string());
-
-        let how_generated = "generated during unknown phase";
-
-        let metadata = String::from("{\"traceback\":null}");
-        let file_name = self.json_path.clone();
-
-        let output_string = format!("{{\"type\":\"{typ}\",\
-        \"run_start\":{run_start},\
-        \"property\":\"{prop}\",\
-        \"status\":\"{status}\",\
-        \"status_reason\":\"{sr}\",\
-        \"representation\":\"{rep}\",\
-        \"arguments\":{arg},\
-        \"how_generated\":\"{hg}\",\
-        \"features\":{feat},\
-        \"metadata\":{meta},\
-        \"coverage\":{cov}}}", typ="test_case", run_start=self.json_time.as_secs().to_string(),prop=property,
-        sr= status_reason, rep=self.representation, arg=self.arguments, hg=how_generated, feat=self.features,
-        meta=metadata, cov=self.coverage);
         let file = std::fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(file_name)?;
-        
-        let mut buffered_writer = std::io::BufWriter::new(file);
-        writeln!(buffered_writer, "{}", output_string);
-        let _ = buffered_writer.flush();
-        Ok(())
+            .create(true)
+            .append(true)
+            .open(file_name)?;
 
+        // write the line in a single call so concurrent writers don't interleave
+        let line = format!("{observation}\n");
+        (&file).write_all(line.as_bytes())?;
+        Ok(())
     }
 }
 
-
 impl Drop for Outcome<'_> {
     fn drop(&mut self) {
         eprintln!("{}", self.to_string());
+        if !self.statistics.is_empty() {
+            eprint!("{}", self.statistics.histogram(self.total));
+        }
     }
 }
 
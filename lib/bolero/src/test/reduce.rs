@@ -0,0 +1,109 @@
+//! Corpus reduction for `cargo bolero reduce`
+//!
+//! `BOLERO_REDUCE_INPUTS` is set to a file listing the corpus inputs, one path per line. Each input
+//! is replayed in order and its signature is printed for `cargo bolero reduce`, which keeps the
+//! smallest set of inputs that covers all of the signatures. The signature is made up of the
+//! executed lines when the binary is instrumented for coverage, otherwise it's a hash of the
+//! generated value.
+
+use super::{coverage::Lines, input, NamedTest};
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    path::PathBuf,
+};
+
+#[derive(Debug)]
+pub struct Reduce {
+    inputs: Vec<PathBuf>,
+}
+
+impl Reduce {
+    /// Returns the inputs to reduce, if `cargo bolero reduce` provided any
+    pub fn new() -> Option<Self> {
+        let path = std::env::var_os("BOLERO_REDUCE_INPUTS").filter(|path| !path.is_empty())?;
+        let inputs = std::fs::read_to_string(&path)
+            .unwrap_or_else(|err| panic!("invalid BOLERO_REDUCE_INPUTS: {err}"));
+        let inputs = inputs
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(PathBuf::from)
+            .collect();
+        Some(Self { inputs })
+    }
+
+    /// Returns the inputs to replay
+    ///
+    /// The first test case generates a value and the following ones mutate the previous value,
+    /// which executes different lines. The first input is replayed twice so every input that's
+    /// reported executes the same code.
+    pub fn tests(&self) -> impl Iterator<Item = NamedTest> + '_ {
+        let warmup = self.inputs.first();
+        warmup
+            .into_iter()
+            .chain(&self.inputs)
+            .map(|path| NamedTest {
+                name: format!("{}", path.display()),
+                data: input::Test::file(path.clone()),
+                origin: input::Origin::Replay,
+            })
+    }
+
+    /// Prints the signature of the test case at `index` for `cargo bolero reduce`
+    pub fn on_result(&self, index: usize, representation: &str, lines: &Lines, failed: bool) {
+        // skip the warmup
+        let Some(index) = index.checked_sub(1) else {
+            return;
+        };
+
+        let features = features(representation, lines)
+            .iter()
+            .map(|feature| feature.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        println!(
+            "[bolero-reduce]{{\"input\":{index},\"failed\":{failed},\"features\":[{features}]}}"
+        );
+    }
+}
+
+/// Returns the signature of a test case as a sorted list of hashes
+fn features(representation: &str, lines: &Lines) -> Vec<u64> {
+    // binaries without coverage don't report any lines
+    if lines.is_empty() {
+        return vec![hash(representation)];
+    }
+
+    let mut features: Vec<u64> = lines
+        .iter()
+        .flat_map(|(file, lines)| lines.iter().map(move |line| hash((file, line))))
+        .collect();
+    features.sort_unstable();
+    features.dedup();
+    features
+}
+
+fn hash<T: Hash>(value: T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn features_test() {
+        // equal values have the same signature without coverage
+        let lines = Lines::new();
+        assert_eq!(features("[1, 2]", &lines), features("[1, 2]", &lines));
+        assert_ne!(features("[1, 2]", &lines), features("[2, 1]", &lines));
+
+        // the value is ignored with coverage
+        let mut lines = Lines::new();
+        lines.insert("lib.rs".to_string(), vec![1, 2, 3]);
+        assert_eq!(features("[1, 2]", &lines), features("[2, 1]", &lines));
+        assert_eq!(features("[1, 2]", &lines).len(), 3);
+    }
+}
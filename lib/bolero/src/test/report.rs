@@ -68,6 +68,15 @@ impl Report {
         self.stats.window_valid = 0;
     }
 
+    /// Sends the runs since the last report to the supervisor
+    pub fn flush(&mut self) {
+        if self.worker.is_some() && self.stats.window_runs > 0 {
+            self.stats.print_worker();
+            self.stats.window_runs = 0;
+            self.stats.window_valid = 0;
+        }
+    }
+
     pub fn on_estimate(&mut self, estimate: f64) {
         self.stats.estimate = Some(estimate);
     }
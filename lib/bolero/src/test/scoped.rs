@@ -0,0 +1,101 @@
+//! Shrinking for scoped tests
+//!
+//! Scoped tests draw their values with `bolero::any` while they run, so there isn't a generator
+//! that the shrinker can replay. Instead, the test itself is rerun with a driver that reads the
+//! bytes being shrunk, and every value it draws is reported along with its call site.
+
+use super::{input, token::Kind};
+use bolero_engine::{
+    any::scope::{self, Draw},
+    driver::{self, object::Object},
+    panic::PanicError,
+    Input, IntoResult, Test,
+};
+use core::{cell::RefCell, fmt, panic::RefUnwindSafe};
+
+/// Runs a scoped test as a [`Test`] over recorded bytes
+pub struct ScopedTest<'a, T> {
+    // `generate_value` only borrows the test immutably but still needs to run it
+    test: RefCell<&'a mut T>,
+    kind: Kind,
+    options: &'a driver::Options,
+}
+
+impl<'a, T> ScopedTest<'a, T> {
+    pub fn new(test: &'a mut T, kind: Kind, options: &'a driver::Options) -> Self {
+        Self {
+            test: RefCell::new(test),
+            kind,
+            options,
+        }
+    }
+}
+
+impl<T, R> Test for ScopedTest<'_, T>
+where
+    T: FnMut() -> R + RefUnwindSafe,
+    R: IntoResult,
+{
+    type Value = Draws;
+
+    fn test<I: Input<Result<bool, PanicError>>>(
+        &mut self,
+        input: &mut I,
+    ) -> Result<bool, PanicError> {
+        let (kind, options) = (self.kind, self.options);
+        let test = self.test.get_mut();
+        input.with_slice(&mut |bytes| run(test, kind, bytes, options))
+    }
+
+    fn generate_value<I: Input<Self::Value>>(&self, input: &mut I) -> Self::Value {
+        let mut test = self.test.borrow_mut();
+        input.with_slice(&mut |bytes| {
+            let (_, draws) = scope::record(|| run(&mut **test, self.kind, bytes, self.options));
+            Draws(draws)
+        })
+    }
+}
+
+fn run<T, R>(
+    test: &mut T,
+    kind: Kind,
+    bytes: &[u8],
+    options: &driver::Options,
+) -> Result<bool, PanicError>
+where
+    T: FnMut() -> R + RefUnwindSafe,
+    R: IntoResult,
+{
+    let bytes = bytes.to_vec();
+    match kind {
+        Kind::Bytes => {
+            let driver = driver::bytes::Driver::new(bytes, options);
+            bolero_engine::any::run(Box::new(Object(driver)), test).1
+        }
+        Kind::Rng => {
+            let driver = driver::Rng::new(input::ReplayRng::new(bytes), options);
+            bolero_engine::any::run(Box::new(Object(driver)), test).1
+        }
+        Kind::Exhaustive => unreachable!("replayed by the exhaustive driver"),
+    }
+}
+
+/// The values drawn with `bolero::any` during a test run
+pub struct Draws(pub Vec<Draw>);
+
+impl fmt::Debug for Draws {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "<no values were drawn>");
+        }
+
+        for (idx, draw) in self.0.iter().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{draw}")?;
+        }
+
+        Ok(())
+    }
+}
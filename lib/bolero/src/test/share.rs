@@ -0,0 +1,188 @@
+//! Sharing interesting inputs between the workers of `cargo bolero test --jobs`
+//!
+//! An input is interesting if it fails, executes new lines, or records a new event, a new
+//! `cover!` hit or a new maximum `target` score. The workers are instrumented for coverage, so
+//! the lines are available unless the profile format isn't supported. Each worker reports the
+//! token of its interesting inputs to the supervisor, which removes duplicates by their content
+//! and writes them to the shared corpus in `BOLERO_RANDOM_SHARE_DIR`. The workers periodically
+//! pick up the inputs written for the others and replay them.
+
+use super::{coverage::Lines, input, token::Token, NamedTest};
+use crate::observe::Record;
+use core::cmp::Ordering;
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+/// How often the shared corpus is checked for new inputs
+const SYNC_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct Share {
+    dir: PathBuf,
+    /// The files of the shared corpus that were run or reported by this worker
+    seen: HashSet<PathBuf>,
+    signals: Signals,
+    last_sync: Option<Instant>,
+}
+
+impl Share {
+    /// Returns the shared state of a `cargo bolero test --jobs` worker, if this is one
+    pub fn new() -> Option<Self> {
+        std::env::var_os("BOLERO_RANDOM_WORKER")?;
+        let dir = std::env::var_os("BOLERO_RANDOM_SHARE_DIR").filter(|dir| !dir.is_empty())?;
+
+        Some(Self {
+            dir: PathBuf::from(dir),
+            seen: HashSet::new(),
+            signals: Signals::default(),
+            last_sync: None,
+        })
+    }
+
+    /// Returns the inputs that were added to the shared corpus since the last call
+    ///
+    /// The directory is only read once per `SYNC_INTERVAL`. The first call also returns the
+    /// inputs shared by previous runs.
+    pub fn sync(&mut self) -> Vec<NamedTest> {
+        if matches!(self.last_sync, Some(last_sync) if last_sync.elapsed() < SYNC_INTERVAL) {
+            return vec![];
+        }
+        self.last_sync = Some(Instant::now());
+
+        let Ok(dir) = std::fs::read_dir(&self.dir) else {
+            return vec![];
+        };
+
+        let mut paths: Vec<PathBuf> = dir
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .map_or(false, |name| !name.starts_with('.'))
+            })
+            .filter(|path| self.seen.insert(path.clone()))
+            .collect();
+
+        // replay the inputs in a consistent order
+        paths.sort_unstable();
+
+        paths
+            .into_iter()
+            .map(|path| NamedTest {
+                name: format!("{}", path.display()),
+                data: input::Test::file(path),
+                origin: input::Origin::Shared,
+            })
+            .collect()
+    }
+
+    /// Records the signals of a valid input, returning `true` if any of them were new
+    pub fn on_result(&mut self, record: &Record, lines: &Lines) -> bool {
+        self.signals.insert(record, lines)
+    }
+
+    /// Reports an interesting input to the supervisor
+    ///
+    /// Inputs that are already in the shared corpus aren't reported again.
+    pub fn report(&mut self, token: &Token) {
+        // the supervisor names the file the same way as the crashes directory
+        if self.seen.insert(super::input_path(&self.dir, token)) {
+            println!("[bolero-share]{{\"token\":\"{token}\"}}");
+        }
+    }
+}
+
+/// The signals that have been observed by this worker
+#[derive(Default)]
+struct Signals {
+    lines: HashMap<String, HashSet<u32>>,
+    events: HashSet<String>,
+    covers: HashSet<String>,
+    targets: HashMap<String, f64>,
+}
+
+impl Signals {
+    /// Records the signals of a test case, returning `true` if any of them were new
+    fn insert(&mut self, record: &Record, lines: &Lines) -> bool {
+        let mut is_new = false;
+
+        for (file, lines) in lines {
+            // avoid allocating for files that were already seen
+            if !self.lines.contains_key(file) {
+                self.lines.insert(file.clone(), HashSet::new());
+            }
+            let seen = self.lines.get_mut(file).unwrap();
+            for line in lines {
+                is_new |= seen.insert(*line);
+            }
+        }
+
+        for label in &record.events {
+            if !self.events.contains(label) {
+                is_new |= self.events.insert(label.clone());
+            }
+        }
+
+        for (label, cover) in &record.covers {
+            if cover.hit && !self.covers.contains(label) {
+                is_new |= self.covers.insert(label.clone());
+            }
+        }
+
+        for (label, score) in &record.targets {
+            match self.targets.get_mut(label) {
+                // scores that aren't comparable are never new
+                Some(max) if score.partial_cmp(max) != Some(Ordering::Greater) => {}
+                Some(max) => {
+                    *max = *score;
+                    is_new = true;
+                }
+                None => {
+                    self.targets.insert(label.clone(), *score);
+                    is_new = true;
+                }
+            }
+        }
+
+        is_new
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signals_test() {
+        let mut signals = Signals::default();
+
+        // only lines that weren't executed before are new
+        let record = Record::default();
+        let mut lines = Lines::new();
+        lines.insert("lib.rs".to_string(), vec![1, 2]);
+        assert!(signals.insert(&record, &lines));
+        assert!(!signals.insert(&record, &lines));
+
+        lines.insert("lib.rs".to_string(), vec![3]);
+        assert!(signals.insert(&record, &lines));
+
+        let mut record = Record::default();
+        record.targets.insert("len".to_string(), 1.0);
+        assert!(signals.insert(&record, &lines));
+        assert!(!signals.insert(&record, &lines));
+
+        // only a higher score is new
+        record.targets.insert("len".to_string(), 0.5);
+        assert!(!signals.insert(&record, &lines));
+        record.targets.insert("len".to_string(), 2.0);
+        assert!(signals.insert(&record, &lines));
+
+        record.events.insert("empty".to_string());
+        assert!(signals.insert(&record, &lines));
+        assert!(!signals.insert(&record, &lines));
+    }
+}
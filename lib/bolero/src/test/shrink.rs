@@ -0,0 +1,322 @@
+//! Parallel and interruptible shrinking
+//!
+//! Setting `BOLERO_SHRINK_JOBS` above one evaluates the candidates of each shrinking transform at
+//! the same time. Tests aren't required to be thread-safe, so each candidate is replayed by a
+//! child process of the test binary, which only runs the input passed in
+//! `BOLERO_SHRINK_CANDIDATE`. Targets built with `TestTarget::with_shrink_threads` run the
+//! candidates on worker threads with copies of the test instead.
+//!
+//! Passes added with `TestTarget::with_shrink_pass` run alongside the built-in passes.
+//!
+//! While a failure is being shrunk, the smallest failing input is kept in the crashes directory,
+//! so it isn't lost if shrinking is interrupted. Inputs that are replayed by the rng driver are
+//! saved as a token in a `.token` file.
+//!
+//! `cargo bolero tmin` sets `BOLERO_TMIN_OUTPUT` to the path where the shrunken input is written.
+//! The generated value and the panic message are written next to it, with `.txt` appended to the
+//! file name.
+
+use super::{
+    input,
+    token::{self, Token},
+    Child,
+};
+use bolero_engine::{
+    driver,
+    shrink::{Executor, Hooks, OnBest, ShrinkPass},
+    Failure, Fork, TargetLocation, Test,
+};
+use core::fmt::Debug;
+use std::{path::PathBuf, process::Stdio, sync::Arc};
+
+/// Evaluates shrinking candidates in child processes of the test binary
+#[derive(Clone, Debug)]
+pub struct Processes {
+    child: Child,
+    kind: token::Kind,
+}
+
+impl Processes {
+    /// Returns an executor for the target if more than one shrink job is configured
+    pub fn new(location: &TargetLocation, options: &driver::Options) -> Option<Self> {
+        if options.shrink_jobs_or_default() <= 1 {
+            return None;
+        }
+
+        Some(Self {
+            child: Child::new(location)?,
+            kind: token::Kind::Bytes,
+        })
+    }
+
+    /// Sets how the candidate bytes are read by the test
+    fn with_kind(mut self, kind: token::Kind) -> Self {
+        self.kind = kind;
+        self
+    }
+}
+
+impl Executor for Processes {
+    fn is_failing(&self, input: &[u8]) -> bool {
+        let token = Token {
+            kind: self.kind,
+            bytes: input.to_vec(),
+        };
+
+        self.child
+            .command()
+            .env("BOLERO_SHRINK_CANDIDATE", token.to_string())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map_or(false, |status| !status.success())
+    }
+}
+
+/// Evaluates shrinking candidates with copies of the test on the shrinker's worker threads
+pub struct Threads<'a, T> {
+    fork: Arc<Fork<'a, T>>,
+    kind: token::Kind,
+    options: driver::Options,
+}
+
+impl<T: Test> Executor for Threads<'_, T> {
+    fn is_failing(&self, input: &[u8]) -> bool {
+        let mut test = (self.fork)();
+        let result = match self.kind {
+            token::Kind::Rng => test.test(&mut input::ReplayRng::input(input, &self.options)),
+            _ => test.test(&mut input::Bytes::new(input, &self.options)),
+        };
+        result.is_err()
+    }
+}
+
+/// Keeps the smallest failing input found so far on disk
+///
+/// The input is written to the `cargo bolero tmin` output if there is one, and otherwise to the
+/// crashes directory.
+#[derive(Debug)]
+pub struct Best {
+    dir: Option<PathBuf>,
+    artifact: Option<PathBuf>,
+    kind: token::Kind,
+    /// The file written for the previous input, unless it was already there
+    saved: Option<PathBuf>,
+    /// Set once the location of the input has been printed
+    announced: bool,
+}
+
+impl Best {
+    fn new(dir: Option<PathBuf>, artifact: Option<PathBuf>, kind: token::Kind) -> Self {
+        // the tmin output is the only file written while minimizing
+        let dir = if artifact.is_some() { None } else { dir };
+        Self {
+            dir,
+            artifact,
+            kind,
+            saved: None,
+            announced: false,
+        }
+    }
+
+    pub fn save(&mut self, input: &[u8]) {
+        if !self.announced {
+            self.announced = true;
+            if let Some(path) = self.artifact.as_ref().or(self.dir.as_ref()) {
+                eprintln!(
+                    "Saving the smallest failing input to {} while shrinking",
+                    path.display()
+                );
+            }
+        }
+
+        if let Some(artifact) = self.artifact.as_ref() {
+            let _ = std::fs::write(artifact, input);
+            return;
+        }
+
+        let Some(dir) = self.dir.as_ref() else {
+            return;
+        };
+
+        let token = Token {
+            kind: self.kind,
+            bytes: input.to_vec(),
+        };
+        let path = super::input_path(dir, &token);
+        if self.saved.as_ref() == Some(&path) {
+            return;
+        }
+
+        // inputs that were saved before shrinking started are left in place
+        let existed = path.exists();
+        if super::save_input(dir, &token).is_none() {
+            return;
+        }
+
+        if let Some(prev) = self.saved.take() {
+            let _ = std::fs::remove_file(prev);
+        }
+        if !existed {
+            self.saved = Some(path);
+        }
+    }
+
+    /// Saves the shrunken input, returning the path in the crashes directory
+    pub fn finish(mut self, input: &[u8]) -> Option<PathBuf> {
+        self.save(input);
+        let dir = self.dir.as_ref()?;
+        let token = Token {
+            kind: self.kind,
+            bytes: input.to_vec(),
+        };
+        Some(super::input_path(dir, &token))
+    }
+}
+
+/// Shrinks the failures of a target
+#[derive(Default)]
+pub struct Context<'a> {
+    /// The executors for candidates that are read as bytes and as rng replays
+    executors: Option<(Box<dyn Executor + 'a>, Box<dyn Executor + 'a>)>,
+    passes: Vec<Box<dyn ShrinkPass>>,
+    /// The directory where the smallest failing input is kept while shrinking
+    crashes: Option<PathBuf>,
+    /// The path provided with `BOLERO_TMIN_OUTPUT`
+    artifact: Option<PathBuf>,
+}
+
+impl<'a> Context<'a> {
+    pub fn new(
+        location: &TargetLocation,
+        options: &driver::Options,
+        passes: Vec<Box<dyn ShrinkPass>>,
+        crashes: Option<PathBuf>,
+    ) -> Self {
+        let executors = Processes::new(location, options).map(|executor| {
+            let rng = executor.clone().with_kind(token::Kind::Rng);
+            (
+                Box::new(executor) as Box<dyn Executor + 'a>,
+                Box::new(rng) as Box<dyn Executor + 'a>,
+            )
+        });
+        let artifact = std::env::var_os("BOLERO_TMIN_OUTPUT")
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from);
+        Self {
+            executors,
+            passes,
+            crashes,
+            artifact,
+        }
+    }
+
+    /// Evaluates the candidates with copies of the test instead of child processes
+    ///
+    /// This only applies if more than one shrink job is configured.
+    pub fn with_fork<T: Test + 'a>(mut self, fork: Fork<'a, T>, options: &driver::Options) -> Self {
+        if options.shrink_jobs_or_default() <= 1 {
+            return self;
+        }
+
+        let fork = Arc::new(fork);
+        let executor = |kind| Threads {
+            fork: fork.clone(),
+            kind,
+            options: options.clone(),
+        };
+        self.executors = Some((
+            Box::new(executor(token::Kind::Bytes)),
+            Box::new(executor(token::Kind::Rng)),
+        ));
+        self
+    }
+
+    /// Returns the hooks for shrinking an input that's read as `kind`
+    pub fn hooks<'b>(&'b self, kind: token::Kind, on_best: Option<OnBest<'b>>) -> Hooks<'b> {
+        let mut hooks = Hooks::default().with_passes(&self.passes);
+
+        let executor = self.executors.as_ref().map(|(bytes, rng)| match kind {
+            token::Kind::Rng => rng,
+            _ => bytes,
+        });
+        if let Some(executor) = executor {
+            hooks = hooks.with_executor(executor.as_ref());
+        }
+
+        if let Some(on_best) = on_best {
+            hooks = hooks.with_on_best(on_best);
+        }
+
+        hooks
+    }
+
+    /// Returns where the smallest failing input that's read as `kind` is kept while shrinking
+    pub fn best(&self, kind: token::Kind) -> Best {
+        Best::new(self.crashes.clone(), self.artifact.clone(), kind)
+    }
+
+    /// Writes a shrunken failure for `cargo bolero tmin`, returning the path of the input
+    pub fn save_artifact<I: Debug>(&self, input: &[u8], failure: &Failure<I>) -> Option<PathBuf> {
+        let path = self.artifact.clone()?;
+
+        let mut description = path.clone().into_os_string();
+        description.push(".txt");
+
+        std::fs::write(&path, input).ok()?;
+        std::fs::write(
+            description,
+            format!(
+                "Input: \n{:#?}\n\nError: \n{}\n",
+                failure.input, failure.error
+            ),
+        )
+        .ok()?;
+
+        Some(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{crash_path, save_crash},
+        *,
+    };
+
+    #[test]
+    fn best_test() {
+        let dir = std::env::temp_dir().join(format!("bolero-{}-best", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        // an input that was already saved isn't removed
+        let existing = save_crash(&dir, &[9]).unwrap();
+
+        let mut best = Best::new(Some(dir.clone()), None, token::Kind::Bytes);
+        best.save(&[1, 2, 3]);
+        assert_eq!(best.saved, Some(crash_path(&dir, &[1, 2, 3])));
+        best.save(&[9]);
+        assert_eq!(best.saved, None);
+        best.save(&[1]);
+        best.save(&[1]);
+        assert_eq!(best.saved, Some(crash_path(&dir, &[1])));
+
+        let mut files: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        files.sort();
+        let mut expected = vec![existing, crash_path(&dir, &[1])];
+        expected.sort();
+        assert_eq!(files, expected);
+
+        // rng inputs are saved as a token
+        let best = Best::new(Some(dir.clone()), None, token::Kind::Rng);
+        let path = best.finish(&[2]).unwrap();
+        assert_eq!(path, crash_path(&dir, &[2]).with_extension("token"));
+        let token: Token = std::fs::read_to_string(&path).unwrap().parse().unwrap();
+        assert_eq!(token, Token::rng(vec![2]));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
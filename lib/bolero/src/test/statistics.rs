@@ -0,0 +1,180 @@
+use crate::observe::Record;
+use core::fmt;
+use std::collections::BTreeMap;
+
+/// Aggregates the events, targets, and coverage requirements recorded across all of the test
+/// cases in a run
+#[derive(Default)]
+pub struct Statistics {
+    events: BTreeMap<String, u64>,
+    targets: BTreeMap<String, Target>,
+    covers: BTreeMap<String, Cover>,
+    valid: u64,
+}
+
+impl Statistics {
+    pub fn on_record(&mut self, record: &Record) {
+        for label in &record.events {
+            *self.events.entry(label.clone()).or_default() += 1;
+        }
+        for (label, score) in &record.targets {
+            self.targets
+                .entry(label.clone())
+                .or_insert_with(Target::new)
+                .on_score(*score);
+        }
+    }
+
+    /// Counts the coverage requirements of a test case that wasn't rejected
+    pub fn on_valid(&mut self, record: &Record) {
+        self.valid += 1;
+        for (label, cover) in &record.covers {
+            let stats = self.covers.entry(label.clone()).or_default();
+            stats.hits += cover.hit as u64;
+            stats.min_percent = cover.min_percent;
+        }
+    }
+
+    /// Adds a coverage requirement before any test case reaches it
+    pub fn declare_cover(&mut self, label: &str, min_percent: f64) {
+        self.covers
+            .entry(label.to_string())
+            .or_default()
+            .min_percent = min_percent;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty() && self.targets.is_empty() && self.covers.is_empty()
+    }
+
+    /// Returns a description of each coverage requirement that wasn't met
+    pub fn unmet_covers(&self) -> Vec<String> {
+        self.covers
+            .iter()
+            .filter(|(_, cover)| cover.percent(self.valid) < cover.min_percent)
+            .map(|(label, cover)| {
+                format!(
+                    "{label:?} was covered in {:.1}% of {} valid test cases, but {}% is required",
+                    cover.percent(self.valid),
+                    self.valid,
+                    cover.min_percent,
+                )
+            })
+            .collect()
+    }
+
+    /// Returns a `Display` implementation of the histogram relative to `total` test cases
+    pub fn histogram(&self, total: u64) -> Histogram<'_> {
+        Histogram { stats: self, total }
+    }
+}
+
+struct Target {
+    count: u64,
+    min: f64,
+    max: f64,
+    sum: f64,
+}
+
+impl Target {
+    fn new() -> Self {
+        Self {
+            count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            sum: 0.0,
+        }
+    }
+
+    fn on_score(&mut self, score: f64) {
+        self.count += 1;
+        self.min = self.min.min(score);
+        self.max = self.max.max(score);
+        self.sum += score;
+    }
+}
+
+#[derive(Default)]
+struct Cover {
+    hits: u64,
+    min_percent: f64,
+}
+
+impl Cover {
+    fn percent(&self, valid: u64) -> f64 {
+        if valid == 0 {
+            return 0.0;
+        }
+        self.hits as f64 / valid as f64 * 100.0
+    }
+}
+
+pub struct Histogram<'a> {
+    stats: &'a Statistics,
+    total: u64,
+}
+
+impl fmt::Display for Histogram<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.total.max(1) as f64;
+
+        if !self.stats.events.is_empty() {
+            writeln!(f, "  events:")?;
+        }
+        for (label, count) in &self.stats.events {
+            let percent = *count as f64 / total * 100.0;
+            writeln!(f, "    {percent:5.1}% {label} ({count})")?;
+        }
+
+        if !self.stats.targets.is_empty() {
+            writeln!(f, "  targets:")?;
+        }
+        for (label, target) in &self.stats.targets {
+            let mean = target.sum / target.count as f64;
+            writeln!(
+                f,
+                "    {label}: min {}, mean {mean:.2}, max {} ({} cases)",
+                target.min, target.max, target.count
+            )?;
+        }
+
+        if !self.stats.covers.is_empty() {
+            writeln!(f, "  coverage requirements:")?;
+        }
+        for (label, cover) in &self.stats.covers {
+            let percent = cover.percent(self.stats.valid);
+            writeln!(
+                f,
+                "    {percent:5.1}% {label} (required {}%)",
+                cover.min_percent
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observe::Payload;
+
+    #[test]
+    fn payload_features_are_not_events_test() {
+        let mut record = Record::default();
+        record
+            .features
+            .insert("even".to_string(), Payload::String(String::new()));
+        record.events.insert("even".to_string());
+        record
+            .features
+            .insert("len".to_string(), Payload::Integer(3));
+
+        let mut stats = Statistics::default();
+        stats.on_record(&record);
+
+        let histogram = stats.histogram(1).to_string();
+        assert!(histogram.contains("even (1)"), "{histogram}");
+        assert!(!histogram.contains("len"), "{histogram}");
+    }
+}
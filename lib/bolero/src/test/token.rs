@@ -0,0 +1,226 @@
+//! Versioned failure tokens
+//!
+//! `BOLERO_RANDOM_SEED` only reproduces a failure while the rng, the driver sampling, and every
+//! generator stay byte-for-byte identical. A token instead records the bytes that the generators
+//! consumed, along with the driver that consumed them, so it keeps reproducing the failure when
+//! the rng implementation changes.
+//!
+//! Tokens are formatted as `bolero1:<kind>:<hex bytes>` and can be replayed by setting
+//! `BOLERO_FAILURE_TOKEN`.
+//!
+//! Exhaustive tokens hold the frame state of the exhaustive driver instead, with each frame
+//! encoded as a LEB128 varint. Besides replaying a single case, they can be passed to
+//! `BOLERO_EXHAUSTIVE_RESUME` to continue the enumeration from that case.
+
+use core::{fmt, str::FromStr};
+
+const VERSION: &str = "bolero1";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub kind: Kind,
+    pub bytes: Vec<u8>,
+}
+
+/// How the recorded bytes are replayed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// The bytes are read directly by the generators, like a corpus file
+    Bytes,
+    /// The bytes are returned by the rng of the random driver
+    Rng,
+    /// The bytes encode the frame state of the exhaustive driver
+    Exhaustive,
+}
+
+impl Kind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Bytes => "bytes",
+            Self::Rng => "rng",
+            Self::Exhaustive => "exhaustive",
+        }
+    }
+}
+
+impl Token {
+    pub fn bytes(bytes: Vec<u8>) -> Self {
+        Self {
+            kind: Kind::Bytes,
+            bytes,
+        }
+    }
+
+    pub fn rng(bytes: Vec<u8>) -> Self {
+        Self {
+            kind: Kind::Rng,
+            bytes,
+        }
+    }
+
+    pub fn exhaustive(frames: &[u64]) -> Self {
+        let mut bytes = vec![];
+        for frame in frames {
+            let mut frame = *frame;
+            loop {
+                let byte = (frame & 0x7f) as u8;
+                frame >>= 7;
+                if frame == 0 {
+                    bytes.push(byte);
+                    break;
+                }
+                bytes.push(byte | 0x80);
+            }
+        }
+
+        Self {
+            kind: Kind::Exhaustive,
+            bytes,
+        }
+    }
+
+    /// Decodes the frame state of an exhaustive token
+    pub fn frames(&self) -> Result<Vec<u64>, String> {
+        if self.kind != Kind::Exhaustive {
+            return Err(format!(
+                "expected an exhaustive token, got {:?}",
+                self.kind.as_str()
+            ));
+        }
+
+        let mut frames = vec![];
+        let mut frame = 0u64;
+        let mut shift = 0;
+        for (idx, byte) in self.bytes.iter().enumerate() {
+            let value = (*byte & 0x7f) as u64;
+            if shift >= 64 || (value << shift) >> shift != value {
+                return Err(format!("frame at offset {idx} overflows a u64"));
+            }
+            frame |= value << shift;
+            shift += 7;
+
+            if byte & 0x80 == 0 {
+                frames.push(frame);
+                frame = 0;
+                shift = 0;
+            }
+        }
+
+        if shift != 0 {
+            return Err("the last frame is truncated".to_string());
+        }
+
+        Ok(frames)
+    }
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{VERSION}:{}:", self.kind.as_str())?;
+        for byte in &self.bytes {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Token {
+    type Err = String;
+
+    fn from_str(token: &str) -> Result<Self, Self::Err> {
+        let mut parts = token.trim().splitn(3, ':');
+        let (Some(version), Some(kind), Some(bytes)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(format!("expected `{VERSION}:<kind>:<bytes>`"));
+        };
+
+        if version != VERSION {
+            return Err(format!(
+                "unsupported version {version:?}; this version of bolero reads {VERSION:?} tokens"
+            ));
+        }
+
+        let kind = match kind {
+            "bytes" => Kind::Bytes,
+            "rng" => Kind::Rng,
+            "exhaustive" => Kind::Exhaustive,
+            _ => return Err(format!("unknown kind {kind:?}")),
+        };
+
+        if bytes.len() % 2 != 0 {
+            return Err("the bytes should have an even number of hex digits".to_string());
+        }
+
+        let bytes = (0..bytes.len())
+            .step_by(2)
+            .map(|idx| {
+                bytes
+                    .get(idx..idx + 2)
+                    .filter(|byte| byte.bytes().all(|b| b.is_ascii_hexdigit()))
+                    .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                    .ok_or_else(|| format!("invalid hex byte at offset {idx}"))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let token = Self { kind, bytes };
+        if kind == Kind::Exhaustive {
+            token.frames()?;
+        }
+
+        Ok(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_test() {
+        for token in [
+            Token::bytes(vec![]),
+            Token::bytes(vec![0, 1, 0xab, 0xff]),
+            Token::rng(vec![0x0a, 0, 0, 0]),
+        ] {
+            let encoded = token.to_string();
+            assert_eq!(encoded.parse::<Token>(), Ok(token), "{encoded}");
+        }
+
+        assert_eq!(Token::rng(vec![0x0a, 0xff]).to_string(), "bolero1:rng:0aff");
+    }
+
+    #[test]
+    fn exhaustive_test() {
+        for frames in [vec![], vec![0], vec![1, 255], vec![0, 127, 128, u64::MAX]] {
+            let encoded = Token::exhaustive(&frames).to_string();
+            let token = encoded.parse::<Token>().unwrap();
+            assert_eq!(token.frames(), Ok(frames), "{encoded}");
+        }
+
+        assert_eq!(
+            Token::exhaustive(&[1, 255]).to_string(),
+            "bolero1:exhaustive:01ff01"
+        );
+    }
+
+    #[test]
+    fn invalid_test() {
+        for token in [
+            "",
+            "bolero1:bytes",
+            "bolero2:bytes:00",
+            "bolero1:other:00",
+            "bolero1:bytes:0",
+            "bolero1:bytes:zz",
+            "bolero1:bytes:+f",
+            "bolero1:bytes:é0",
+            "bolero1:exhaustive:80",
+            "bolero1:exhaustive:ffffffffffffffffff7f",
+        ] {
+            assert!(
+                token.parse::<Token>().is_err(),
+                "{token:?} should be invalid"
+            );
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! Per-input timeouts
+//!
+//! A test case can't be interrupted once it's running, so a background thread checks how long the
+//! current case has been running. Once it exceeds `BOLERO_RANDOM_TIMEOUT_MS`, the case is reported
+//! as a hang and the process exits.
+//!
+//! The value can only be generated by the test, which is still running, so the hang is described
+//! by a child process of the test binary. It replays the input with `BOLERO_DESCRIBE_HANG` set,
+//! which generates the value without testing it and saves the input to the `hangs` directory next
+//! to `crashes`, in the same format as crash files.
+
+use super::{input, token::Token, Child};
+use bolero_engine::Seed;
+use std::{
+    cell::RefCell,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+thread_local! {
+    /// The state of the watchdog for the test running on this thread
+    static ACTIVE: RefCell<Option<Arc<Mutex<Option<Case>>>>> = const { RefCell::new(None) };
+}
+
+/// The test case that's currently running
+struct Case {
+    started: Instant,
+    input: Replay,
+}
+
+/// Identifies an input so a child process can replay it
+enum Replay {
+    Seed(Seed),
+    File(PathBuf),
+    Token(Token),
+}
+
+impl Replay {
+    fn new(data: &input::Test) -> Self {
+        match data {
+            input::Test::Rng(test) => Self::Seed(test.seed),
+            input::Test::File(file) => Self::File(file.path.clone()),
+            input::Test::Token(token) => Self::Token(token.clone()),
+        }
+    }
+}
+
+pub struct Watchdog {
+    current: Arc<Mutex<Option<Case>>>,
+}
+
+impl Watchdog {
+    /// Starts watching the test cases that are run on the current thread
+    pub fn new(timeout: Duration, child: Option<Child>) -> Self {
+        let current = Arc::new(Mutex::new(None));
+        ACTIVE.with(|active| *active.borrow_mut() = Some(current.clone()));
+
+        let state = current.clone();
+        let interval = (timeout / 10).clamp(Duration::from_millis(10), Duration::from_secs(1));
+        std::thread::spawn(move || {
+            while Arc::strong_count(&state) > 1 {
+                std::thread::sleep(interval);
+
+                let current = state.lock().unwrap_or_else(|err| err.into_inner());
+                if let Some(case) = current.as_ref() {
+                    if case.started.elapsed() > timeout {
+                        on_hang(case, timeout, child.as_ref());
+                    }
+                }
+            }
+        });
+
+        Self { current }
+    }
+
+    /// Starts timing a test case
+    #[inline]
+    pub fn start(&self, data: &input::Test) {
+        *self.lock() = Some(Case {
+            started: Instant::now(),
+            input: Replay::new(data),
+        });
+    }
+
+    /// Stops timing the current test case
+    #[inline]
+    pub fn finish(&self) {
+        *self.lock() = None;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<Case>> {
+        self.current.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        ACTIVE.with(|active| *active.borrow_mut() = None);
+    }
+}
+
+/// Executes `f` without counting its time towards the current test case
+pub fn suspend<F: FnOnce() -> R, R>(f: F) -> R {
+    let Some(current) = ACTIVE.with(|active| active.borrow().clone()) else {
+        return f();
+    };
+
+    let start = Instant::now();
+    let result = f();
+
+    if let Some(case) = current
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .as_mut()
+    {
+        case.started += start.elapsed();
+    }
+
+    result
+}
+
+fn on_hang(case: &Case, timeout: Duration, child: Option<&Child>) -> ! {
+    use std::{fmt::Write as _, io::Write as _};
+
+    // the report is written at once, since the test is still running and could print too
+    let mut report = String::new();
+    let _ = writeln!(
+        report,
+        "\n======================== Test Timeout ========================\n"
+    );
+    let _ = writeln!(
+        report,
+        "The test case exceeded the timeout of {:?}\n",
+        timeout
+    );
+
+    let _ = match &case.input {
+        Replay::Seed(seed) => writeln!(report, "BOLERO_RANDOM_SEED={}\n", seed),
+        Replay::File(path) => writeln!(report, "Input file: {}\n", path.display()),
+        Replay::Token(token) => writeln!(report, "BOLERO_FAILURE_TOKEN={}\n", token),
+    };
+
+    if let Some(child) = child {
+        let mut cmd = child.command();
+        cmd.env("BOLERO_REPLAY", "1")
+            .env("BOLERO_DESCRIBE_HANG", "1")
+            .env_remove("BOLERO_RANDOM_SEED")
+            .env_remove("BOLERO_REPLAY_FILE");
+        match &case.input {
+            Replay::Seed(seed) => cmd.env("BOLERO_RANDOM_SEED", seed.to_string()),
+            Replay::File(path) => cmd.env("BOLERO_REPLAY_FILE", path),
+            Replay::Token(token) => cmd.env("BOLERO_FAILURE_TOKEN", token.to_string()),
+        };
+        // the child prints the value and where the input was saved, once the file is written
+        if let Ok(output) = cmd.output() {
+            report.push_str(&String::from_utf8_lossy(&output.stderr));
+        }
+    }
+
+    report.push_str("\n==============================================================\n");
+
+    // exiting doesn't wait for the other threads, so the report is flushed first
+    let _ = std::io::stdout().flush();
+    let mut stderr = std::io::stderr().lock();
+    let _ = stderr.write_all(report.as_bytes());
+    let _ = stderr.flush();
+
+    std::process::exit(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suspend_test() {
+        let watchdog = Watchdog::new(Duration::from_secs(3600), None);
+        watchdog.start(&input::Test::Rng(input::RngTest { seed: 1 }));
+        let started = watchdog.lock().as_ref().unwrap().started;
+
+        suspend(|| std::thread::sleep(Duration::from_millis(20)));
+
+        // the suspended time isn't counted towards the case
+        let case = watchdog.lock();
+        assert!(case.as_ref().unwrap().started >= started + Duration::from_millis(20));
+        drop(case);
+
+        watchdog.finish();
+        assert!(watchdog.lock().is_none());
+    }
+}
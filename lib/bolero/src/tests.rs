@@ -95,7 +95,34 @@ fn with_exhaustive() {
         });
 
     assert_eq!(num_iters.load(Ordering::Relaxed), 256);
-    assert_eq!(total_value.load(Ordering::Relaxed), (0..=255).sum());
+    assert_eq!(
+        total_value.load(Ordering::Relaxed),
+        (0..=255).sum::<usize>()
+    );
+}
+
+#[test]
+fn with_exhaustive_by_size() {
+    let seen = std::sync::Mutex::new(vec![]);
+
+    check!()
+        .with_generator((0..4u8, 0..4u8))
+        .cloned()
+        .exhaustive_by_size()
+        .for_each(|value| seen.lock().unwrap().push(value));
+
+    // values are first tested in order of their size
+    let mut first = vec![];
+    for value in seen.into_inner().unwrap() {
+        if !first.contains(&value) {
+            first.push(value);
+        }
+    }
+    assert_eq!(first.len(), 16);
+    let sizes: Vec<_> = first.iter().map(|(a, b)| a + b).collect();
+    let mut sorted = sizes.clone();
+    sorted.sort();
+    assert_eq!(sizes, sorted);
 }
 
 #[test]
@@ -197,3 +224,818 @@ fn scope_exhaustive_panic_test() {
         assert!(any::<bool>(), "oops");
     });
 }
+
+fn tyche_path(name: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("bolero-{}-{name}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn tyche_observations_test() {
+    let path = tyche_path("observations");
+
+    let mut target = check!();
+    target.engine.with_tyche_path(path.clone());
+    target
+        .with_type::<(u8, Vec<u8>)>()
+        .with_iterations(10)
+        .for_each(|(value, list)| {
+            crate::argument("value", value);
+            crate::event_with_payload("len", list.len());
+            crate::event_with_payload("kind", "list");
+        });
+
+    let observations = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let lines: Vec<_> = observations.lines().collect();
+    assert_eq!(lines.len(), 10);
+    for line in lines {
+        assert!(line.contains(r#""arguments":{"value":""#), "{line}");
+        assert!(
+            line.contains(r#""features":{"kind":"list","len":"#),
+            "{line}"
+        );
+    }
+}
+
+#[test]
+fn cover_test() {
+    check!().with_type::<u8>().exhaustive().for_each(|value| {
+        cover!(*value < 128, 50.0, "lower half");
+        cover!(*value % 4 == 0, 20.0, "multiple of 4");
+    });
+}
+
+#[test]
+#[should_panic]
+fn cover_unmet_test() {
+    check!().with_type::<u8>().exhaustive().for_each(|value| {
+        cover!(*value == 0, 5.0, "zero");
+    });
+}
+
+#[test]
+#[should_panic]
+fn cover_unreached_test() {
+    // the requirement is declared even though its `cover!` is never reached
+    check!()
+        .with_type::<u8>()
+        .with_cover("overflow", 1.0)
+        .exhaustive()
+        .for_each(|value| {
+            if value.checked_add(1).is_none() && *value != u8::MAX {
+                cover!(true, 1.0, "overflow");
+            }
+        });
+}
+
+#[test]
+fn cover_replay_test() {
+    let path = std::env::temp_dir().join(format!("bolero-{}-cover-replay", std::process::id()));
+    std::fs::write(&path, [1]).unwrap();
+
+    // a single replayed input doesn't reach the iteration limit so the covers aren't checked
+    let mut target = check!();
+    target.engine.with_replay_file(path.clone());
+    target.with_type::<u8>().for_each(|value| {
+        cover!(*value == 0, 50.0, "zero");
+    });
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn cover_rejected_test() {
+    // rejected inputs don't count towards the requirement
+    check!()
+        .with_type::<u8>()
+        .filter(|value| *value < 16)
+        .exhaustive()
+        .for_each(|value| {
+            cover!(*value < 8, 50.0, "lower half");
+        });
+}
+
+#[test]
+fn tyche_event_target_test() {
+    let path = tyche_path("event_target");
+
+    let mut target = check!();
+    target.engine.with_tyche_path(path.clone());
+    target.with_type::<u8>().exhaustive().for_each(|value| {
+        if value % 2 == 0 {
+            crate::event("even");
+        }
+        crate::target(*value as f64, "value");
+    });
+
+    let observations = read_observations(&path);
+    assert_eq!(observations.len(), 256);
+
+    let mut even = 0;
+    for observation in &observations {
+        let features = observation["features"].as_object().unwrap();
+        let value: u8 = observation["representation"]
+            .as_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(features["target:value"].as_f64(), Some(value as f64));
+        if value % 2 == 0 {
+            even += 1;
+            assert_eq!(features["even"].as_str(), Some(""));
+        } else {
+            assert!(!features.contains_key("even"));
+        }
+    }
+    assert_eq!(even, 128);
+}
+
+#[test]
+#[cfg(bolero_coverage)]
+fn tyche_coverage_test() {
+    let path = tyche_path("coverage");
+    let branches = std::sync::Mutex::new(vec![]);
+
+    let mut target = check!();
+    target.engine.with_tyche_path(path.clone());
+    target
+        .with_type::<bool>()
+        .with_iterations(20)
+        .for_each(|value| {
+            // record the line of the branch that was taken along with the one that wasn't
+            let lines = if *value {
+                (line!(), line!() + 2)
+            } else {
+                (line!(), line!() - 2)
+            };
+            branches.lock().unwrap().push(lines);
+        });
+
+    let branches = branches.into_inner().unwrap();
+    let observations = read_observations(&path);
+    assert_eq!(observations.len(), branches.len());
+
+    for (observation, (taken, skipped)) in observations.iter().zip(branches) {
+        let coverage = observation["coverage"].as_object().unwrap();
+        let (_, lines) = coverage
+            .iter()
+            .find(|(file, _)| file.ends_with(file!()))
+            .expect("missing coverage for the test file");
+        let lines: Vec<_> = lines
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|line| line.as_u64().unwrap() as u32)
+            .collect();
+
+        assert!(lines.contains(&taken), "{taken} should be covered");
+        assert!(!lines.contains(&skipped), "{skipped} should not be covered");
+    }
+}
+
+/// Strings that are commonly mishandled when embedded in JSON
+const ADVERSARIAL: &[&str] = &[
+    "",
+    "\"",
+    "\\",
+    "\\\"",
+    "\"},{\"status\":\"failed",
+    "line\nbreak\r\n",
+    "\t\u{0}\u{8}\u{c}\u{1b}\u{1f}\u{7f}",
+    "é ☃ 🦀 \u{2028}\u{2029} \u{feff}",
+];
+
+/// A value whose `Debug` output is written without any escaping
+#[derive(Clone)]
+struct Adversarial(String);
+
+impl core::fmt::Debug for Adversarial {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+fn adversarial() -> impl ValueGenerator<Output = Adversarial> {
+    (0..=ADVERSARIAL.len(), produce::<String>()).map_gen(|(idx, random)| {
+        Adversarial(ADVERSARIAL.get(idx).map_or(random, |v| v.to_string()))
+    })
+}
+
+fn read_observations(path: &std::path::Path) -> Vec<serde_json::Map<String, serde_json::Value>> {
+    let observations = std::fs::read_to_string(path).unwrap();
+    let _ = std::fs::remove_file(path);
+
+    observations
+        .lines()
+        .map(|line| {
+            let value: serde_json::Value = serde_json::from_str(line)
+                .unwrap_or_else(|err| panic!("invalid observation {line:?}: {err}"));
+            let serde_json::Value::Object(observation) = value else {
+                panic!("observation should be an object: {line:?}");
+            };
+
+            let mut keys: Vec<_> = observation.keys().map(String::as_str).collect();
+            keys.sort_unstable();
+            assert_eq!(
+                keys,
+                [
+                    "arguments",
+                    "coverage",
+                    "features",
+                    "how_generated",
+                    "metadata",
+                    "property",
+                    "representation",
+                    "run_start",
+                    "status",
+                    "status_reason",
+                    "type"
+                ]
+            );
+            assert_eq!(observation["type"], "test_case");
+
+            observation
+        })
+        .collect()
+}
+
+#[test]
+fn tyche_round_trip_test() {
+    let path = tyche_path("round_trip");
+    let seen = std::sync::Mutex::new(vec![]);
+
+    let mut target = check!();
+    target.engine.with_tyche_path(path.clone());
+    target
+        .with_generator(adversarial())
+        .with_iterations(200)
+        .for_each(|value| {
+            crate::argument(&value.0, value);
+            crate::event_with_payload(&value.0, value.0.as_str());
+            seen.lock().unwrap().push(value.0.clone());
+        });
+
+    let seen = seen.into_inner().unwrap();
+    let observations = read_observations(&path);
+    assert_eq!(observations.len(), seen.len());
+
+    for (observation, value) in observations.iter().zip(&seen) {
+        assert_eq!(observation["status"], "passed");
+        assert!(observation["how_generated"]
+            .as_str()
+            .unwrap()
+            .starts_with("generated from seed "));
+        assert_eq!(observation["representation"], value.as_str());
+        assert_eq!(observation["arguments"][value.as_str()], value.as_str());
+        assert_eq!(observation["features"][value.as_str()], value.as_str());
+    }
+
+    for value in ADVERSARIAL {
+        assert!(seen.iter().any(|v| v == value), "{value:?} was not tested");
+    }
+}
+
+#[test]
+fn tyche_round_trip_failure_test() {
+    let path = tyche_path("round_trip_failure");
+
+    std::panic::catch_unwind(|| {
+        let mut target = check!();
+        target.engine.with_tyche_path(path.clone());
+        target
+            .with_generator(adversarial())
+            .with_shrink_time(Duration::ZERO)
+            .for_each(|value| {
+                assert!(value.0.is_empty(), "{}", value.0);
+            });
+    })
+    .unwrap_err();
+
+    let observations = read_observations(&path);
+    let (failure, passed) = observations.split_last().unwrap();
+
+    for observation in passed {
+        assert_eq!(observation["status"], "passed");
+        assert_eq!(observation["representation"], "");
+    }
+
+    assert_eq!(failure["status"], "failed");
+    assert_ne!(failure["representation"], "");
+}
+
+#[test]
+fn tyche_exhaustive_test() {
+    let path = tyche_path("exhaustive");
+
+    let mut target = check!();
+    target.engine.with_tyche_path(path.clone());
+    target.with_type::<u8>().exhaustive().for_each(|value| {
+        crate::argument("value", value);
+    });
+
+    let observations = read_observations(&path);
+    assert_eq!(observations.len(), 256);
+
+    for (observation, value) in observations.iter().zip(0..=255u8) {
+        assert_eq!(observation["status"], "passed");
+        assert_eq!(
+            observation["how_generated"],
+            "generated by exhaustive enumeration"
+        );
+        assert_eq!(observation["representation"], value.to_string());
+        assert_eq!(observation["arguments"]["value"], value.to_string());
+    }
+}
+
+#[test]
+fn tyche_scope_test() {
+    let path = tyche_path("scope");
+    let drawn = std::sync::Mutex::new(vec![]);
+
+    let mut target = check!();
+    target.engine.with_tyche_path(path.clone());
+    target.with_iterations(20).run(|| {
+        let a: u8 = any();
+        let b: bool = any();
+        drawn.lock().unwrap().push((a, b));
+    });
+
+    let drawn = drawn.into_inner().unwrap();
+    let observations = read_observations(&path);
+    assert_eq!(observations.len(), drawn.len());
+
+    for (observation, (a, b)) in observations.iter().zip(drawn) {
+        let representation = observation["representation"].as_str().unwrap();
+        let lines: Vec<_> = representation.lines().collect();
+        assert_eq!(lines.len(), 2, "{representation}");
+
+        let prefix = format!("[{}:", file!());
+        assert!(lines[0].starts_with(&prefix), "{representation}");
+        assert!(lines[0].ends_with(&format!("] {a}")), "{representation}");
+        assert!(lines[1].starts_with(&prefix), "{representation}");
+        assert!(lines[1].ends_with(&format!("] {b}")), "{representation}");
+    }
+}
+
+#[test]
+fn filter_rejected_test() {
+    check!()
+        .with_type::<u8>()
+        .filter(|value| value % 2 == 0)
+        .with_iterations(100)
+        .for_each(|value| assert_eq!(value % 2, 0));
+}
+
+#[test]
+fn tyche_filter_gave_up_test() {
+    let path = tyche_path("filter_gave_up");
+
+    let mut target = check!();
+    target.engine.with_tyche_path(path.clone());
+    let line = line!() + 3;
+    target
+        .with_type::<u8>()
+        .filter(|value| *value < 16)
+        .exhaustive()
+        .for_each(|value| assert!(*value < 16));
+
+    let observations = read_observations(&path);
+    assert_eq!(observations.len(), 256);
+
+    let location = format!("{}:{line}:", file!());
+    let mut gave_up = 0;
+    for observation in &observations {
+        let reason = observation["status_reason"].as_str().unwrap();
+        match observation["status"].as_str().unwrap() {
+            "passed" => assert_eq!(reason, ""),
+            "gave_up" => {
+                gave_up += 1;
+                assert!(reason.starts_with("rejected by filter at "), "{reason}");
+                assert!(reason.contains(&location), "{reason}");
+            }
+            status => panic!("unexpected status {status:?}"),
+        }
+    }
+    assert_eq!(gave_up, 240);
+}
+
+#[test]
+fn tyche_assume_gave_up_test() {
+    let path = tyche_path("assume_gave_up");
+
+    let mut target = check!();
+    target.engine.with_tyche_path(path.clone());
+    let line = line!() + 3;
+    target.exhaustive().run(|| {
+        let value: bool = any();
+        crate::assume(value, "value must be true");
+    });
+
+    let observations = read_observations(&path);
+    let statuses: Vec<_> = observations
+        .iter()
+        .map(|observation| {
+            (
+                observation["status"].as_str().unwrap(),
+                observation["status_reason"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    let reason = format!(
+        "rejected by assume at {}:{line}:9: value must be true",
+        file!()
+    );
+    assert_eq!(
+        statuses,
+        [("gave_up", reason.as_str()), ("passed", "")],
+        "{statuses:?}"
+    );
+}
+
+#[test]
+fn tyche_scope_exhaustive_test() {
+    let path = tyche_path("scope_exhaustive");
+
+    let mut target = check!();
+    target.engine.with_tyche_path(path.clone());
+    target.exhaustive().run(|| {
+        let _: bool = any();
+    });
+
+    let observations = read_observations(&path);
+    let representations: Vec<_> = observations
+        .iter()
+        .map(|observation| {
+            let representation = observation["representation"].as_str().unwrap();
+            representation.rsplit(' ').next().unwrap().to_string()
+        })
+        .collect();
+    assert_eq!(representations, ["false", "true"]);
+}
+
+#[test]
+fn tyche_shrink_test() {
+    let path = tyche_path("shrink");
+
+    std::panic::catch_unwind(|| {
+        let mut target = check!();
+        target.engine.with_tyche_path(path.clone());
+        target.with_type::<u8>().for_each(|value| {
+            assert!(*value < 10);
+        });
+    })
+    .unwrap_err();
+
+    let observations = read_observations(&path);
+    let [.., failure, shrunken] = &observations[..] else {
+        panic!("missing shrunken observation");
+    };
+
+    assert_eq!(failure["status"], "failed");
+    let how_generated = failure["how_generated"].as_str().unwrap();
+    let seed = how_generated.strip_prefix("generated from ").unwrap();
+
+    assert_eq!(shrunken["status"], "failed");
+    assert_eq!(shrunken["how_generated"], format!("shrunk from {seed}"));
+    assert_eq!(shrunken["representation"], "10");
+}
+
+#[test]
+fn tyche_scope_shrink_test() {
+    // both the rng bytes and the corpus bytes should be shrunk
+    for token in [None, Some("bolero1:bytes:ff")] {
+        let path = tyche_path("scope-shrink");
+
+        std::panic::catch_unwind(|| {
+            let mut target = check!();
+            target.engine.with_tyche_path(path.clone());
+            if let Some(token) = token {
+                target.engine.with_token(token);
+            }
+            target.run(|| {
+                let value: u8 = any();
+                assert!(value < 10);
+            });
+        })
+        .unwrap_err();
+
+        let observations = read_observations(&path);
+        let [.., failure, shrunken] = &observations[..] else {
+            panic!("missing shrunken observation");
+        };
+
+        assert_eq!(failure["status"], "failed");
+        assert_eq!(shrunken["status"], "failed");
+
+        // each drawn value is listed with its call site
+        let representation = shrunken["representation"].as_str().unwrap();
+        let (location, value) = representation.split_once("] ").unwrap();
+        assert!(
+            location.starts_with(&format!("[{}:", file!())),
+            "{location}"
+        );
+        assert_eq!(value, "10", "{token:?}");
+    }
+}
+
+#[test]
+fn save_crash_test() {
+    let dir = std::env::temp_dir().join(format!("bolero-{}-crashes", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    std::panic::catch_unwind(|| {
+        let mut target = check!();
+        target.engine.with_crashes_dir(dir.clone());
+        target.with_type::<u8>().for_each(|value| {
+            assert!(*value < 10);
+        });
+    })
+    .unwrap_err();
+
+    let files: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(files.len(), 1);
+
+    // the saved input should replay the shrunken failure
+    let input = std::fs::read(&files[0]).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+    let options = Default::default();
+    let mut driver = bolero_generator::driver::ByteSliceDriver::new(&input, &options);
+    assert_eq!(produce::<u8>().generate(&mut driver), Some(10));
+}
+
+#[test]
+fn save_unshrunken_crash_test() {
+    let dir = std::env::temp_dir().join(format!("bolero-{}-unshrunken", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let failed = std::sync::Mutex::new(None);
+    std::panic::catch_unwind(|| {
+        let mut target = check!().with_shrink_time(Duration::ZERO);
+        target.engine.with_crashes_dir(dir.clone());
+        target.with_type::<Vec<u16>>().for_each(|value| {
+            if value.len() > 3 {
+                *failed.lock().unwrap() = Some(value.clone());
+                panic!("too long");
+            }
+        });
+    })
+    .unwrap_err();
+
+    let files: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].extension().unwrap(), "token");
+
+    // the saved token should replay the original failure
+    let replayed = std::sync::Mutex::new(None);
+    std::panic::catch_unwind(|| {
+        let mut target = check!();
+        target.engine.with_replay_file(files[0].clone());
+        target.with_type::<Vec<u16>>().for_each(|value| {
+            *replayed.lock().unwrap() = Some(value.clone());
+            assert!(value.len() <= 3);
+        });
+    })
+    .unwrap_err();
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let failed = failed.into_inner().unwrap();
+    assert!(failed.is_some());
+    assert_eq!(replayed.into_inner().unwrap(), failed);
+}
+
+#[test]
+fn save_best_file_shrink_test() {
+    let dir = std::env::temp_dir().join(format!("bolero-{}-best-file", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.with_extension("input");
+    std::fs::write(&path, [200]).unwrap();
+
+    // the inputs on disk at each run, which is what an interrupted shrink would leave
+    let snapshots = std::sync::Mutex::new(vec![]);
+    std::panic::catch_unwind(|| {
+        let mut target = check!();
+        target.engine.with_crashes_dir(dir.clone());
+        target.engine.with_replay_file(path.clone());
+        target.with_type::<u8>().for_each(|value| {
+            let saved: Vec<_> = std::fs::read_dir(&dir)
+                .into_iter()
+                .flatten()
+                .map(|entry| std::fs::read(entry.unwrap().path()).unwrap())
+                .collect();
+            snapshots.lock().unwrap().push(saved);
+            assert!(*value < 10);
+        });
+    })
+    .unwrap_err();
+
+    let files: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| std::fs::read(entry.unwrap().path()).unwrap())
+        .collect();
+    let _ = std::fs::remove_dir_all(&dir);
+    let _ = std::fs::remove_file(&path);
+
+    // a smaller failing input was on disk before the shrink finished
+    let snapshots = snapshots.into_inner().unwrap();
+    assert!(snapshots
+        .iter()
+        .any(|saved| matches!(&saved[..], [input] if (10..200).contains(&input[0]))));
+    assert_eq!(files, [vec![10]]);
+}
+
+#[test]
+fn save_best_scope_shrink_test() {
+    let dir = std::env::temp_dir().join(format!("bolero-{}-best-scope", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    std::panic::catch_unwind(|| {
+        let mut target = check!();
+        target.engine.with_crashes_dir(dir.clone());
+        target.run(|| {
+            let value: u8 = any();
+            assert!(value < 10);
+        });
+    })
+    .unwrap_err();
+
+    let files: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    let [path] = &files[..] else {
+        panic!("expected a single input in {files:?}");
+    };
+
+    // the rng bytes are saved as a token, which replays the shrunken failure
+    assert_eq!(path.extension().unwrap(), "token");
+    let seen = std::sync::Mutex::new(vec![]);
+    let mut target = check!();
+    target.engine.with_replay_file(path.clone());
+    target.run(|| seen.lock().unwrap().push(any::<u8>()));
+
+    let _ = std::fs::remove_dir_all(&dir);
+    assert_eq!(seen.into_inner().unwrap(), [10]);
+}
+
+#[test]
+fn shrink_threads_test() {
+    let threads = std::sync::Mutex::new(std::collections::HashSet::new());
+    let last_seen_value = AtomicU8::new(0);
+
+    std::panic::catch_unwind(|| {
+        check!()
+            .with_type::<u8>()
+            .with_shrink_jobs(4)
+            .with_shrink_threads()
+            .for_each(|value| {
+                threads.lock().unwrap().insert(std::thread::current().id());
+                last_seen_value.store(*value, Ordering::Relaxed);
+                assert!(*value < 10);
+            });
+    })
+    .unwrap_err();
+
+    // the candidates were run by the worker threads
+    assert!(threads.into_inner().unwrap().len() > 1);
+    assert_eq!(last_seen_value.load(Ordering::Relaxed), 10);
+}
+
+#[test]
+fn hang_replay_test() {
+    let dir = std::env::temp_dir().join(format!("bolero-{}-hangs", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let seed = 42;
+
+    // the value generated from the seed, which the rng driver reads differently from bytes
+    let expected = std::sync::Mutex::new(vec![]);
+    let mut target = check!();
+    target.engine.with_seed(seed);
+    target
+        .with_type::<Vec<u16>>()
+        .with_iterations(0)
+        .for_each(|value| expected.lock().unwrap().push(value.clone()));
+
+    // the hang is described without running the test
+    let tested = AtomicUsize::new(0);
+    let mut target = check!();
+    target.engine.with_seed(seed);
+    target.engine.with_describe_hang(dir.clone());
+    target.with_type::<Vec<u16>>().for_each(|_| {
+        tested.fetch_add(1, Ordering::Relaxed);
+    });
+    assert_eq!(tested.load(Ordering::Relaxed), 0);
+
+    let files: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    let [path] = &files[..] else {
+        panic!("expected a single input in {files:?}");
+    };
+
+    let seen = std::sync::Mutex::new(vec![]);
+    let mut target = check!();
+    target.engine.with_replay_file(path.clone());
+    target
+        .with_type::<Vec<u16>>()
+        .for_each(|value| seen.lock().unwrap().push(value.clone()));
+
+    let _ = std::fs::remove_dir_all(&dir);
+    assert_eq!(seen.into_inner().unwrap(), expected.into_inner().unwrap());
+}
+
+#[test]
+fn token_replay_test() {
+    for (token, expected) in [
+        // the rng driver samples a `u8` from 4 bytes
+        ("bolero1:rng:0a000000", 10),
+        ("bolero1:bytes:0b", 11),
+    ] {
+        let seen = std::sync::Mutex::new(vec![]);
+
+        let mut target = check!();
+        target.engine.with_token(token);
+        target
+            .with_type::<u8>()
+            .with_iterations(0)
+            .for_each(|value| seen.lock().unwrap().push(*value));
+
+        assert_eq!(seen.into_inner().unwrap(), [expected], "{token}");
+    }
+}
+
+#[test]
+fn token_scope_replay_test() {
+    let seen = std::sync::Mutex::new(vec![]);
+
+    let mut target = check!();
+    target.engine.with_token("bolero1:bytes:0c");
+    target
+        .with_iterations(0)
+        .run(|| seen.lock().unwrap().push(any::<u8>()));
+
+    assert_eq!(seen.into_inner().unwrap(), [12]);
+}
+
+#[test]
+fn replay_file_test() {
+    let path = std::env::temp_dir().join(format!("bolero-{}-replay", std::process::id()));
+    std::fs::write(&path, [13]).unwrap();
+
+    // only the replayed file is run, even for exhaustive targets
+    let seen = std::sync::Mutex::new(vec![]);
+    let mut target = check!();
+    target.engine.with_replay_file(path.clone());
+    target
+        .with_type::<u8>()
+        .exhaustive()
+        .for_each(|value| seen.lock().unwrap().push(*value));
+
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(seen.into_inner().unwrap(), [13]);
+}
+
+#[test]
+fn exhaustive_token_replay_test() {
+    let seen = std::sync::Mutex::new(vec![]);
+
+    // exhaustive tokens are replayed without enabling the exhaustive driver
+    let mut target = check!();
+    target.engine.with_token("bolero1:exhaustive:01ff01");
+    target
+        .with_type::<(u8, u8)>()
+        .for_each(|value| seen.lock().unwrap().push(*value));
+
+    assert_eq!(seen.into_inner().unwrap(), [(1, 255)]);
+
+    let seen = std::sync::Mutex::new(vec![]);
+
+    let mut target = check!();
+    target.engine.with_token("bolero1:exhaustive:0203");
+    target.run(|| seen.lock().unwrap().push((any::<u8>(), any::<u8>())));
+
+    assert_eq!(seen.into_inner().unwrap(), [(2, 3)]);
+}
+
+#[test]
+fn exhaustive_resume_test() {
+    let seen = std::sync::Mutex::new(vec![]);
+
+    let mut target = check!();
+    target.engine.with_resume("bolero1:exhaustive:fc01");
+    target
+        .with_type::<u8>()
+        .exhaustive()
+        .for_each(|value| seen.lock().unwrap().push(*value));
+
+    assert_eq!(seen.into_inner().unwrap(), [252, 253, 254, 255]);
+}
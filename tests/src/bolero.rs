@@ -32,6 +32,12 @@ impl Test {
             cmd!(sh, "cargo test --features arbitrary").run()?;
         }
 
+        // make sure Tyche coverage can be read from instrumented binaries
+        cmd!(sh, "cargo test -p bolero --lib tyche")
+            .env("RUSTFLAGS", "-Cinstrument-coverage --cfg bolero_coverage")
+            .env("LLVM_PROFILE_FILE", "/dev/null")
+            .run()?;
+
         for sub_project in ["bolero-generator", "bolero-engine", "bolero"] {
             cmd!(
                 sh,